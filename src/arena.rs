@@ -0,0 +1,228 @@
+//! The courts a match can be played on, loaded from `assets/arenas/`.
+
+use crate::core::{Side, ARENA_HEIGHT, ARENA_WIDTH};
+use amethyst::assets::{Asset, Handle};
+use amethyst::ecs::VecStorage;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The shape of a court. Any field missing from the file falls back to that
+/// of the classic, empty court.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Arena {
+    pub width: f32,
+    pub height: f32,
+    /// Solid shapes within the court that balls bounce off.
+    pub obstacles: Vec<Obstacle>,
+    /// Where balls may pass through a guarded wall for a point. A guarded wall
+    /// with no goals of its own is open along its whole length.
+    pub goals: Vec<Goal>,
+}
+
+impl Default for Arena {
+    fn default() -> Arena {
+        Arena {
+            width: ARENA_WIDTH,
+            height: ARENA_HEIGHT,
+            obstacles: Vec::new(),
+            goals: Vec::new(),
+        }
+    }
+}
+
+impl Arena {
+    pub fn centre(&self) -> [f32; 2] {
+        [self.width * 0.5, self.height * 0.5]
+    }
+
+    /// Check that the court can be played on, naming the first field that
+    /// can't.
+    pub fn validate(&self) -> Result<(), ArenaError> {
+        check(
+            "width",
+            self.width.is_finite() && self.width > 0.0,
+            "must be a positive number",
+        )?;
+        check(
+            "height",
+            self.height.is_finite() && self.height > 0.0,
+            "must be a positive number",
+        )?;
+
+        for (i, goal) in self.goals.iter().enumerate() {
+            let length = if goal.side.horizontal() {
+                self.width
+            } else {
+                self.height
+            };
+            check(
+                &format!("goals[{}].from", i),
+                goal.from >= 0.0 && goal.from <= goal.to,
+                "must not be negative, nor past `to`",
+            )?;
+            check(
+                &format!("goals[{}].to", i),
+                goal.to <= length,
+                "must not run past the end of its wall",
+            )?;
+        }
+
+        for (i, obstacle) in self.obstacles.iter().enumerate() {
+            match *obstacle {
+                Obstacle::Rect { size, .. } => check(
+                    &format!("obstacles[{}].size", i),
+                    size.iter().all(|n| n.is_finite() && *n > 0.0),
+                    "must be positive numbers",
+                )?,
+                Obstacle::Circle { radius, .. } => check(
+                    &format!("obstacles[{}].radius", i),
+                    radius.is_finite() && radius > 0.0,
+                    "must be a positive number",
+                )?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether a ball reaching the wall on `side` at `along`, measured from the
+    /// bottom or left end of that wall, goes through for a point.
+    pub fn in_goal(&self, side: Side, along: f32) -> bool {
+        let mut goals = self
+            .goals
+            .iter()
+            .filter(|goal| goal.side == side)
+            .peekable();
+
+        goals.peek().is_none() || goals.any(|goal| along >= goal.from && along <= goal.to)
+    }
+}
+
+impl Asset for Arena {
+    const NAME: &'static str = "pong::Arena";
+    type Data = Self;
+    type HandleStorage = VecStorage<Handle<Self>>;
+}
+
+/// Something solid standing in the court.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum Obstacle {
+    /// A block, given by its centre and its full width and height.
+    Rect { centre: [f32; 2], size: [f32; 2] },
+    /// A post, given by its centre and radius.
+    Circle { centre: [f32; 2], radius: f32 },
+}
+
+/// An opening in the wall on `side`, running between `from` and `to` along it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Goal {
+    pub side: Side,
+    pub from: f32,
+    pub to: f32,
+}
+
+fn check(field: &str, ok: bool, reason: &'static str) -> Result<(), ArenaError> {
+    if ok {
+        Ok(())
+    } else {
+        Err(ArenaError {
+            field: field.to_string(),
+            reason,
+        })
+    }
+}
+
+/// A field of an `Arena` held a value the game can't be played on.
+#[derive(Debug)]
+pub struct ArenaError {
+    pub field: String,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for ArenaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid arena field `{}`: {}", self.field, self.reason)
+    }
+}
+
+impl std::error::Error for ArenaError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amethyst::config::Config;
+
+    fn failing_field(arena: &Arena) -> String {
+        arena.validate().unwrap_err().field
+    }
+
+    #[test]
+    fn shipped_arenas_are_valid() {
+        assert!(Arena::default().validate().is_ok());
+
+        for name in ["classic", "fortress", "pillars"].iter() {
+            let arena = Arena::load(format!("assets/arenas/{}.ron", name)).unwrap();
+            assert!(arena.validate().is_ok(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn errors_name_the_bad_field() {
+        let arena = Arena {
+            height: 0.0,
+            ..Arena::default()
+        };
+        assert_eq!(failing_field(&arena), "height");
+
+        let goal = |side, from, to| Arena {
+            goals: vec![Goal { side, from, to }],
+            ..Arena::default()
+        };
+        assert_eq!(
+            failing_field(&goal(Side::Left, 60.0, 40.0)),
+            "goals[0].from"
+        );
+        assert_eq!(
+            failing_field(&goal(Side::Left, -1.0, 40.0)),
+            "goals[0].from"
+        );
+        assert_eq!(failing_field(&goal(Side::Top, 40.0, 101.0)), "goals[0].to");
+
+        let arena = Arena {
+            obstacles: vec![
+                Obstacle::Circle {
+                    centre: [50.0, 50.0],
+                    radius: 5.0,
+                },
+                Obstacle::Rect {
+                    centre: [50.0, 20.0],
+                    size: [6.0, 0.0],
+                },
+            ],
+            ..Arena::default()
+        };
+        assert_eq!(failing_field(&arena), "obstacles[1].size");
+    }
+
+    #[test]
+    fn walls_without_goals_are_open() {
+        let arena = Arena {
+            goals: vec![Goal {
+                side: Side::Left,
+                from: 25.0,
+                to: 75.0,
+            }],
+            ..Arena::default()
+        };
+
+        assert!(arena.in_goal(Side::Left, 25.0));
+        assert!(arena.in_goal(Side::Left, 50.0));
+        assert!(!arena.in_goal(Side::Left, 10.0));
+        assert!(!arena.in_goal(Side::Left, 80.0));
+
+        // The right wall has no goals of its own, so any of it will do.
+        assert!(arena.in_goal(Side::Right, 0.0));
+        assert!(arena.in_goal(Side::Right, 99.0));
+    }
+}
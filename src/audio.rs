@@ -31,15 +31,15 @@ pub fn initialize_audio(world: &mut World) {
         sink.set_volume(0.25);
         let music = MUSIC_TRACKS
             .iter()
-            .map(|file| load_audio_track(&loader, &world, file))
+            .map(|file| load_audio_track(&loader, world, file))
             .collect::<Vec<_>>()
             .into_iter()
             .cycle();
         let music = Music { music };
 
         let sound = Sounds {
-            bounce_sfx: load_audio_track(&loader, &world, BOUNCE_SOUND),
-            score_sfx: load_audio_track(&loader, &world, SCORE_SOUND),
+            bounce_sfx: load_audio_track(&loader, world, BOUNCE_SOUND),
+            score_sfx: load_audio_track(&loader, world, SCORE_SOUND),
         };
 
         (sound, music)
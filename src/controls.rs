@@ -0,0 +1,217 @@
+//! The keys each player plays with, and changing them from within the game.
+
+use amethyst::config::{Config, ConfigError};
+use amethyst::input::{Axis, Bindings, Button, StringBindings};
+use amethyst::winit::VirtualKeyCode;
+use std::path::{Path, PathBuf};
+
+/// The axes players can bind, and how each is named on screen.
+pub const AXES: [(&str, &str); 4] = [
+    ("left_paddle", "P1 Paddle"),
+    ("right_paddle", "P2 Paddle"),
+    ("top_paddle", "P3 Paddle"),
+    ("bottom_paddle", "P4 Paddle"),
+];
+
+/// The actions players can bind, and how each is named on screen.
+pub const ACTIONS: [(&str, &str); 6] = [
+    ("left_serve", "P1 Serve"),
+    ("right_serve", "P2 Serve"),
+    ("top_serve", "P3 Serve"),
+    ("bottom_serve", "P4 Serve"),
+    ("pause", "Pause"),
+    ("quit", "Quit"),
+];
+
+/// Where bindings changed in the game are saved, to be used in place of the
+/// defaults from then on.
+pub struct UserBindings(pub PathBuf);
+
+impl UserBindings {
+    /// Save the `bindings`, creating their directory if need be.
+    pub fn save(&self, bindings: &Bindings<StringBindings>) -> Result<(), ConfigError> {
+        if let Some(dir) = self.0.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        bindings.write(&self.0)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+/// A single thing a key can be bound to: one end of an axis, or an action.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Slot {
+    /// The positive end of the axis, if `true`, or else the negative one.
+    Axis(usize, bool),
+    Action(usize),
+}
+
+impl Slot {
+    /// Every slot, axes first, in the order they're listed on screen.
+    pub fn all() -> Vec<Slot> {
+        let axes = (0..AXES.len()).flat_map(|i| vec![Slot::Axis(i, true), Slot::Axis(i, false)]);
+        let actions = (0..ACTIONS.len()).map(Slot::Action);
+
+        axes.chain(actions).collect()
+    }
+
+    pub fn name(self) -> String {
+        match self {
+            // The first two paddles move up and down, the others side to side.
+            Slot::Axis(i, pos) => {
+                let way = match (i < 2, pos) {
+                    (true, true) => "Up",
+                    (true, false) => "Down",
+                    (false, true) => "Right",
+                    (false, false) => "Left",
+                };
+                format!("{} {}", AXES[i].1, way)
+            }
+            Slot::Action(i) => ACTIONS[i].1.to_string(),
+        }
+    }
+
+    /// Every button currently bound to the slot.
+    pub fn buttons(self, bindings: &Bindings<StringBindings>) -> Vec<Button> {
+        match self {
+            Slot::Axis(i, pos) => match bindings.axis(AXES[i].0) {
+                Some(Axis::Emulated { pos: p, .. }) if pos => vec![*p],
+                Some(Axis::Emulated { neg, .. }) if !pos => vec![*neg],
+                _ => vec![],
+            },
+            Slot::Action(i) => bindings
+                .action_bindings(ACTIONS[i].0)
+                .flat_map(|combo| combo.iter().copied())
+                .collect(),
+        }
+    }
+
+    /// The slot and the buttons bound to it, as a line of the options screen.
+    pub fn describe(self, bindings: &Bindings<StringBindings>) -> String {
+        let buttons: Vec<String> = self.buttons(bindings).iter().map(button_name).collect();
+
+        if buttons.is_empty() {
+            format!("{}  None", self.name())
+        } else {
+            format!("{}  {}", self.name(), buttons.join(" "))
+        }
+    }
+}
+
+/// Bind `button` to `slot` alone, in place of whatever was bound to it before.
+/// A button can't be shared, so one already in use elsewhere is refused.
+pub fn rebind(
+    bindings: &mut Bindings<StringBindings>,
+    slot: Slot,
+    button: Button,
+) -> Result<(), String> {
+    let taken = Slot::all()
+        .into_iter()
+        .filter(|other| *other != slot)
+        .find(|other| other.buttons(bindings).contains(&button));
+
+    if let Some(other) = taken {
+        return Err(format!(
+            "{} is taken by {}",
+            button_name(&button),
+            other.name()
+        ));
+    }
+
+    match slot {
+        Slot::Axis(i, pos) => {
+            let (id, name) = AXES[i];
+            let axis = match bindings.axis(id) {
+                Some(Axis::Emulated { neg, .. }) if pos => Axis::Emulated {
+                    pos: button,
+                    neg: *neg,
+                },
+                Some(Axis::Emulated { pos: p, .. }) => Axis::Emulated {
+                    pos: *p,
+                    neg: button,
+                },
+                _ => return Err(format!("{} isn't played with keys", name)),
+            };
+
+            bindings
+                .insert_axis(id, axis)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        }
+        Slot::Action(i) => {
+            let id = ACTIONS[i].0;
+            let combos: Vec<Vec<Button>> = bindings
+                .action_bindings(id)
+                .map(|combo| combo.to_vec())
+                .collect();
+
+            for combo in combos {
+                let _ = bindings.remove_action_binding(id, &combo);
+            }
+
+            bindings
+                .insert_action_binding(id.to_string(), std::iter::once(button))
+                .map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// How to pause and quit, for the foot of the screen.
+pub fn hints(bindings: &Bindings<StringBindings>) -> String {
+    ["pause", "quit"]
+        .iter()
+        .filter_map(|id| {
+            let i = ACTIONS.iter().position(|(action, _)| action == id)?;
+            let button = *Slot::Action(i).buttons(bindings).first()?;
+            Some(format!("{} to {}", button_name(&button), ACTIONS[i].1))
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+pub fn button_name(button: &Button) -> String {
+    match button {
+        Button::Key(VirtualKeyCode::Escape) => "Esc".to_string(),
+        Button::Key(VirtualKeyCode::Return) => "Enter".to_string(),
+        Button::Key(key) => format!("{:?}", key),
+        button => format!("{:?}", button),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defaults() -> Bindings<StringBindings> {
+        Bindings::load("config/bindings.ron").unwrap()
+    }
+
+    #[test]
+    fn taken_keys_are_refused() {
+        let mut bindings = defaults();
+        let quit = Slot::Action(5);
+        let space = Button::Key(VirtualKeyCode::Space);
+
+        assert_eq!(
+            rebind(&mut bindings, quit, space),
+            Err("Space is taken by P1 Serve".to_string())
+        );
+        assert_eq!(
+            quit.buttons(&bindings),
+            vec![Button::Key(VirtualKeyCode::Q)]
+        );
+
+        // A slot may be given the key it already has, or a free one.
+        let up = Slot::Axis(1, true);
+        assert!(rebind(&mut bindings, up, Button::Key(VirtualKeyCode::Up)).is_ok());
+        assert!(rebind(&mut bindings, quit, Button::Key(VirtualKeyCode::X)).is_ok());
+        assert_eq!(
+            quit.buttons(&bindings),
+            vec![Button::Key(VirtualKeyCode::X)]
+        );
+    }
+}
@@ -1,4 +1,10 @@
+use crate::rules::{PaddleMotion, Rules, SpeedCurve};
+use amethyst::assets::Handle;
 use amethyst::ecs::prelude::{Component, DenseVecStorage, Entity};
+use amethyst::renderer::SpriteSheet;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
 
 pub const ARENA_HEIGHT: f32 = 100.0;
 pub const ARENA_WIDTH: f32 = 100.0;
@@ -6,63 +12,605 @@ pub const ARENA_WIDTH: f32 = 100.0;
 pub const PADDLE_HEIGHT: f32 = 16.0;
 pub const PADDLE_WIDTH: f32 = 4.0;
 
-pub const BALL_VELOCITY_X: f32 = 65.0;
-pub const BALL_VELOCITY_Y: f32 = 50.0;
 pub const BALL_RADIUS: f32 = 2.0;
 
+pub const POWER_UP_RADIUS: f32 = 2.0;
+
+/// The size of the solid blocks filling the corners where two guarded walls
+/// meet, so that paddles can't crowd into them.
+pub const CORNER: f32 = 10.0;
+
+/// How much power-ups change paddle heights, paddle speeds, and ball speeds.
+pub const GROW_FACTOR: f32 = 1.5;
+pub const SHRINK_FACTOR: f32 = 0.6;
+pub const HASTE_FACTOR: f32 = 1.6;
+pub const SLOW_BALL_FACTOR: f32 = 0.5;
+
+/// Where things are on `pong_spritesheet.ron`.
+pub const PADDLE_SPRITE: usize = 0;
+pub const BALL_SPRITE: usize = 1;
+
 /// A component for Entities whose activity can be halted.
+#[derive(Debug, Clone, Copy)]
 pub struct Active {
     /// `None` implies that the entity is active.
     pub countdown: Option<f32>,
+    /// The side whose paddle a ball waits on until it's served, if any.
+    pub held_by: Option<Side>,
 }
 
 impl Component for Active {
     type Storage = DenseVecStorage<Active>;
 }
 
-#[derive(Default)]
+/// A component for Entities that move between steps of the game. Frames that
+/// fall between two steps draw them part of the way from one to the next.
+#[derive(Debug, Clone, Copy)]
+pub struct Interpolated {
+    /// Where the entity was before the latest step.
+    pub previous: [f32; 2],
+    /// Where the entity is as of the latest step.
+    pub current: [f32; 2],
+}
+
+impl Interpolated {
+    pub fn at(position: [f32; 2]) -> Interpolated {
+        Interpolated {
+            previous: position,
+            current: position,
+        }
+    }
+
+    /// Where to draw the entity, `alpha` of the way through the next step.
+    pub fn blend(&self, alpha: f32) -> [f32; 2] {
+        [
+            self.previous[0] + (self.current[0] - self.previous[0]) * alpha,
+            self.previous[1] + (self.current[1] - self.previous[1]) * alpha,
+        ]
+    }
+}
+
+impl Component for Interpolated {
+    type Storage = DenseVecStorage<Interpolated>;
+}
+
+/// The state of a match: points in the current game, games in the current
+/// set, and sets won. Each is kept for every `Side`, indexed by `Side::index`.
+#[derive(Debug, Default, Clone)]
 pub struct ScoreBoard {
-    pub score_left: u32,
-    pub score_right: u32,
+    pub points: [u32; 4],
+    pub games: [u32; 4],
+    pub sets: [u32; 4],
+    /// The games won by each side in every finished set.
+    pub set_scores: Vec<[u32; 4]>,
+    /// The points won by each side in the last finished game.
+    pub last_game: [u32; 4],
+    /// The points each side begins every game with.
+    pub head_start: [u32; 4],
+    /// The most paddle hits in any one rally.
+    pub longest_rally: u32,
+    /// How long the match has been played for, in seconds.
+    pub duration: f32,
+    /// The fastest any ball has gone.
+    pub top_speed: f32,
+}
+
+impl ScoreBoard {
+    /// A fresh match, where each side begins every game with the points in
+    /// `head_start`.
+    pub fn new(head_start: [u32; 4]) -> ScoreBoard {
+        ScoreBoard {
+            points: head_start,
+            head_start,
+            ..ScoreBoard::default()
+        }
+    }
+
+    /// Award a point to `side`, rolling over into games and sets as they're won.
+    pub fn point(&mut self, side: Side, rules: &Rules) {
+        if self.winner(rules).is_some() {
+            return;
+        }
+
+        self.points[side.index()] += 1;
+
+        if let Some(game_winner) = self.game_winner(rules) {
+            self.last_game = self.points;
+            self.points = self.head_start;
+            self.games[game_winner.index()] += 1;
+
+            if self.games[game_winner.index()] >= rules.games_per_set {
+                self.set_scores.push(self.games);
+                self.games = [0; 4];
+                self.sets[game_winner.index()] += 1;
+            }
+        }
+    }
+
+    /// The `Side` that has won the current game, if any. It must lead every
+    /// other side by the margin the rules call for.
+    fn game_winner(&self, rules: &Rules) -> Option<Side> {
+        let margin = if rules.win_by_two { 2 } else { 1 };
+
+        Side::ALL.iter().copied().find(|side| {
+            let points = self.points[side.index()];
+
+            points >= rules.points_per_game
+                && Side::ALL
+                    .iter()
+                    .filter(|other| *other != side)
+                    .all(|other| points >= self.points[other.index()] + margin)
+        })
+    }
+
+    /// The `Side` that has won the match, if any.
+    pub fn winner(&self, rules: &Rules) -> Option<Side> {
+        Side::ALL
+            .iter()
+            .copied()
+            .find(|side| self.sets[side.index()] >= rules.sets_to_win)
+    }
+
+    /// The outcome of the match, once it's over.
+    pub fn result(&self, rules: &Rules) -> Option<MatchResult> {
+        self.winner(rules).map(|winner| MatchResult {
+            winner,
+            sets: self.sets,
+            set_scores: self.set_scores.clone(),
+            last_game: self.last_game,
+            longest_rally: self.longest_rally,
+            duration: self.duration,
+            top_speed: self.top_speed,
+        })
+    }
+
+    /// The text shown on the scoreboard for `side`. Games and sets are only
+    /// shown when the match has more than one of them.
+    pub fn display(&self, side: Side, rules: &Rules) -> String {
+        let i = side.index();
+        let (points, games, sets) = (self.points[i], self.games[i], self.sets[i]);
+
+        if rules.sets_to_win > 1 {
+            format!("{} {} {}", sets, games, points)
+        } else if rules.games_per_set > 1 {
+            format!("{} {}", games, points)
+        } else {
+            points.to_string()
+        }
+    }
+}
+
+/// How a finished match turned out.
+#[derive(Debug, Clone)]
+pub struct MatchResult {
+    pub winner: Side,
+    /// Sets won by each side.
+    pub sets: [u32; 4],
+    /// The games won by each side in every set.
+    pub set_scores: Vec<[u32; 4]>,
+    /// The points won by each side in the deciding game.
+    pub last_game: [u32; 4],
+    pub longest_rally: u32,
+    /// In seconds.
+    pub duration: f32,
+    pub top_speed: f32,
+}
+
+impl MatchResult {
+    /// A one-line summary of the score, listing the `sides` that played in
+    /// order.
+    pub fn summary(&self, rules: &Rules, sides: &[Side]) -> String {
+        let line = |scores: &[u32; 4]| {
+            sides
+                .iter()
+                .map(|side| scores[side.index()].to_string())
+                .collect::<Vec<_>>()
+                .join("-")
+        };
+
+        if rules.sets_to_win > 1 {
+            self.set_scores
+                .iter()
+                .map(line)
+                .collect::<Vec<_>>()
+                .join(" ")
+        } else if rules.games_per_set > 1 {
+            line(&self.set_scores.last().copied().unwrap_or_default())
+        } else {
+            line(&self.last_game)
+        }
+    }
+}
+
+/// A run of matches, won by the first side to take more than half of them.
+/// Kept as a resource for as long as the series lasts.
+#[derive(Debug, Clone, Copy)]
+pub struct Series {
+    pub best_of: u32,
+    /// The matches won by each side so far.
+    pub wins: [u32; 4],
 }
 
-pub struct ScoreText {
-    pub p1_score: Entity,
-    pub p2_score: Entity,
+impl Series {
+    pub fn new(best_of: u32) -> Series {
+        Series {
+            best_of,
+            wins: [0; 4],
+        }
+    }
+
+    /// The matches a side must win to take the series.
+    pub fn to_win(&self) -> u32 {
+        self.best_of / 2 + 1
+    }
+
+    pub fn record(&mut self, winner: Side) {
+        self.wins[winner.index()] += 1;
+    }
+
+    pub fn played(&self) -> u32 {
+        self.wins.iter().sum()
+    }
+
+    /// The `Side` that has won the series, if any.
+    pub fn winner(&self) -> Option<Side> {
+        Side::ALL
+            .iter()
+            .copied()
+            .find(|side| self.wins[side.index()] >= self.to_win())
+    }
+
+    /// The matches won by each of the `sides`, in order.
+    pub fn tally(&self, sides: &[Side]) -> String {
+        sides
+            .iter()
+            .map(|side| self.wins[side.index()].to_string())
+            .collect::<Vec<_>>()
+            .join("-")
+    }
 }
 
+/// The score of each side, on screen.
+pub struct ScoreText(pub Vec<(Side, Entity)>);
+
 /// The "Ready?" message before the ball begins to move.
 pub struct ServeText(pub Entity);
 
 /// The FPS counter.
 pub struct FPS(pub Entity);
 
+/// The balls left to lose in breakout before the game is over.
+#[derive(Debug, Clone, Copy)]
+pub struct Lives(pub u32);
+
+/// The lives left, on screen.
+pub struct LivesText(pub Entity);
+
+/// The length of the current rally and of the longest, on screen in place of
+/// the scores during practice.
+pub struct RallyText {
+    pub current: Entity,
+    pub best: Entity,
+}
+
+/// The sprite sheet for paddles, balls and power-ups, for things that appear
+/// partway through a match.
+pub struct Sprites(pub Handle<SpriteSheet>);
+
+/// The rally in progress, from the last point scored.
+#[derive(Debug, Default, Clone)]
+pub struct Rally {
+    /// Seconds since the last point.
+    pub time: f32,
+    /// Extra balls that have joined during this rally.
+    pub extra_balls: u32,
+    /// Times a ball has been hit by a paddle during this rally.
+    pub hits: u32,
+}
+
+/// The source of all chance in a match. The same seed always gives the same
+/// match, given the same inputs.
+#[derive(Debug, Clone)]
+pub struct Random {
+    pub seed: u64,
+    pub rng: ChaCha8Rng,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        Random {
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+/// The game advances in steps of a fixed length, however long each frame
+/// takes. Frame time builds up until there's enough for another step.
+#[derive(Debug, Clone)]
+pub struct FixedStep {
+    /// The length of a step, in seconds.
+    pub dt: f32,
+    /// Frame time not yet used up by a step.
+    pub accumulator: f32,
+}
+
+impl FixedStep {
+    /// The longest frame that will be caught up on. Anything beyond this is
+    /// dropped, rather than running a flurry of steps after a stall.
+    const MAX_FRAME: f32 = 0.25;
+
+    pub fn new(rules: &Rules) -> FixedStep {
+        FixedStep {
+            dt: 1.0 / rules.steps_per_second as f32,
+            accumulator: 0.0,
+        }
+    }
+
+    /// Bank the time of a frame that has passed.
+    pub fn add(&mut self, frame: f32) {
+        self.accumulator += frame.min(FixedStep::MAX_FRAME);
+    }
+
+    /// Take one step's worth of time, if there's enough of it.
+    pub fn take(&mut self) -> bool {
+        if self.accumulator >= self.dt {
+            self.accumulator -= self.dt;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// How far the game is through the next step, from 0.0 to 1.0.
+    pub fn alpha(&self) -> f32 {
+        self.accumulator / self.dt
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Ball {
     pub velocity: [f32; 2],
     pub radius: f32,
+    pub speed_curve: SpeedCurve,
+    /// Extra balls join during long rallies, and leave once they score.
+    pub extra: bool,
+    /// The `Side` whose paddle last hit the ball during this rally.
+    pub last_hit: Option<Side>,
+    /// How fast the ball's path curves, in radians per second, anticlockwise.
+    pub spin: f32,
+}
+
+impl Ball {
+    pub fn new(rules: &Rules) -> Ball {
+        let angle = rules.serve_angle.to_radians();
+        let base = rules.ball_speed.base;
+
+        Ball {
+            radius: BALL_RADIUS,
+            velocity: [base * angle.cos(), base * angle.sin()],
+            speed_curve: rules.ball_speed,
+            extra: false,
+            last_hit: None,
+            spin: 0.0,
+        }
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.velocity[0].hypot(self.velocity[1])
+    }
+
+    /// Keep the ball's direction, but move at `speed` instead.
+    pub fn set_speed(&mut self, speed: f32) {
+        let current = self.speed();
+
+        if current > 0.0 {
+            let scale = speed.min(self.speed_curve.max) / current;
+            self.velocity[0] *= scale;
+            self.velocity[1] *= scale;
+        }
+    }
+
+    /// The speed the ball should leave a paddle at.
+    pub fn speed_after_hit(&self) -> f32 {
+        (self.speed() + self.speed_curve.per_hit).min(self.speed_curve.max)
+    }
+
+    /// Speed up over `dt` seconds of a rally.
+    pub fn ramp(&mut self, dt: f32) {
+        self.set_speed(self.speed() + self.speed_curve.per_second * dt);
+    }
 }
 
 impl Component for Ball {
     type Storage = DenseVecStorage<Ball>;
 }
 
+/// A wall of the arena, and the player whose paddle guards it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Side {
     Left,
     Right,
+    Top,
+    Bottom,
+}
+
+impl Side {
+    pub const ALL: [Side; 4] = [Side::Left, Side::Right, Side::Top, Side::Bottom];
+
+    /// Where this side's entries are kept in per-side arrays.
+    pub fn index(self) -> usize {
+        match self {
+            Side::Left => 0,
+            Side::Right => 1,
+            Side::Top => 2,
+            Side::Bottom => 3,
+        }
+    }
+
+    /// The side across the arena.
+    pub fn opposite(self) -> Side {
+        match self {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+            Side::Top => Side::Bottom,
+            Side::Bottom => Side::Top,
+        }
+    }
+
+    /// Whether this side's paddle lies along the top or bottom wall, and so
+    /// moves left and right.
+    pub fn horizontal(self) -> bool {
+        matches!(self, Side::Top | Side::Bottom)
+    }
+
+    /// The player guarding this side, as shown on screen.
+    pub fn player(self) -> &'static str {
+        match self {
+            Side::Left => "P1",
+            Side::Right => "P2",
+            Side::Top => "P3",
+            Side::Bottom => "P4",
+        }
+    }
+}
+
+/// Who moves a `Paddle`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Controller {
+    Human,
+    Computer,
+}
+
+/// The `Controller` of each `Side` for a match. Any side without one is a
+/// wall.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Players {
+    pub left: Controller,
+    pub right: Option<Controller>,
+    pub top: Option<Controller>,
+    pub bottom: Option<Controller>,
+}
+
+impl Players {
+    /// A match between two people.
+    pub fn two_player() -> Players {
+        Players {
+            left: Controller::Human,
+            right: Some(Controller::Human),
+            top: None,
+            bottom: None,
+        }
+    }
+
+    /// A match against the computer, which plays on the right.
+    pub fn one_player() -> Players {
+        Players {
+            left: Controller::Human,
+            right: Some(Controller::Computer),
+            top: None,
+            bottom: None,
+        }
+    }
+
+    /// Practice on the left, against the other three walls.
+    pub fn practice() -> Players {
+        Players {
+            left: Controller::Human,
+            right: None,
+            top: None,
+            bottom: None,
+        }
+    }
+
+    /// A match between four people, one on each wall.
+    pub fn four_player() -> Players {
+        Players {
+            left: Controller::Human,
+            right: Some(Controller::Human),
+            top: Some(Controller::Human),
+            bottom: Some(Controller::Human),
+        }
+    }
+
+    /// Who plays on `side`, if anyone.
+    pub fn controller(&self, side: Side) -> Option<Controller> {
+        match side {
+            Side::Left => Some(self.left),
+            Side::Right => self.right,
+            Side::Top => self.top,
+            Side::Bottom => self.bottom,
+        }
+    }
+
+    /// The sides with someone playing on them.
+    pub fn sides(&self) -> Vec<Side> {
+        Side::ALL
+            .iter()
+            .copied()
+            .filter(|side| self.controller(*side).is_some())
+            .collect()
+    }
+
+    /// Whether there's only one player, with nobody to play against.
+    pub fn solo(&self) -> bool {
+        self.sides().len() == 1
+    }
 }
 
+impl Default for Players {
+    fn default() -> Players {
+        Players::two_player()
+    }
+}
+
+/// A paddle guarding one `Side`. Its `height` is its length along that wall,
+/// whichever way the wall runs, and its `width` its thickness.
+#[derive(Debug, Clone)]
 pub struct Paddle {
     pub side: Side,
+    pub controller: Controller,
     pub width: f32,
     pub height: f32,
+    /// The height before any power-up effects.
+    pub base_height: f32,
+    /// A multiplier on how fast the paddle moves.
+    pub speed: f32,
+    /// The speed multiplier before any power-up effects.
+    pub base_speed: f32,
+    /// How fast the paddle moved along its wall during the last step. Positive
+    /// is up or to the right.
+    pub velocity: f32,
+    /// How the paddle gets up to speed and comes to a stop.
+    pub motion: PaddleMotion,
 }
 
 impl Paddle {
-    pub fn new(side: Side) -> Paddle {
+    /// A paddle for `side`, sized and sped up or slowed down by the player's
+    /// handicap, and moving as the rules have it move.
+    pub fn new(side: Side, controller: Controller, rules: &Rules) -> Paddle {
+        let handicap = rules.handicaps.for_side(side);
+        let height = PADDLE_HEIGHT * handicap.paddle_length;
+
         Paddle {
             side,
+            controller,
             width: PADDLE_WIDTH,
-            height: PADDLE_HEIGHT,
+            height,
+            base_height: height,
+            speed: handicap.paddle_speed,
+            base_speed: handicap.paddle_speed,
+            velocity: 0.0,
+            motion: rules.paddle_motion.for_side(side),
+        }
+    }
+
+    /// Half the paddle's extent along the x and y axes.
+    pub fn half_extents(&self) -> [f32; 2] {
+        if self.side.horizontal() {
+            [self.height * 0.5, self.width * 0.5]
+        } else {
+            [self.width * 0.5, self.height * 0.5]
         }
     }
 }
@@ -70,3 +618,184 @@ impl Paddle {
 impl Component for Paddle {
     type Storage = DenseVecStorage<Paddle>;
 }
+
+/// A brick in breakout, which breaks once it has taken `hits` more hits.
+/// `size` is its full width and height.
+#[derive(Debug, Clone)]
+pub struct Brick {
+    pub hits: u32,
+    pub size: [f32; 2],
+}
+
+impl Component for Brick {
+    type Storage = DenseVecStorage<Brick>;
+}
+
+/// The kinds of power-up that can be collected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerKind {
+    /// A longer paddle.
+    Grow,
+    /// A shorter paddle.
+    Shrink,
+    /// A faster paddle.
+    Haste,
+    /// Every ball moves at half speed.
+    SlowBall,
+    /// The ball splits in two, straight away.
+    SplitBall,
+}
+
+impl PowerKind {
+    pub const ALL: [PowerKind; 5] = [
+        PowerKind::Grow,
+        PowerKind::Shrink,
+        PowerKind::Haste,
+        PowerKind::SlowBall,
+        PowerKind::SplitBall,
+    ];
+
+    /// Where this power-up is on `pong_spritesheet.ron`.
+    pub fn sprite_number(self) -> usize {
+        match self {
+            PowerKind::Grow => 2,
+            PowerKind::Shrink => 3,
+            PowerKind::Haste => 4,
+            PowerKind::SlowBall => 5,
+            PowerKind::SplitBall => 6,
+        }
+    }
+}
+
+/// A pickup waiting in the arena for a ball to pass through it.
+#[derive(Debug, Clone)]
+pub struct PowerUp {
+    pub kind: PowerKind,
+    pub radius: f32,
+}
+
+impl Component for PowerUp {
+    type Storage = DenseVecStorage<PowerUp>;
+}
+
+/// The effect of a collected power-up, which wears off after a while.
+#[derive(Debug, Clone)]
+pub struct Effect {
+    pub kind: PowerKind,
+    /// The `Side` that collected it.
+    pub side: Side,
+    /// Seconds until it wears off.
+    pub remaining: f32,
+}
+
+/// Every power-up effect currently in force.
+#[derive(Debug, Default, Clone)]
+pub struct Effects(pub Vec<Effect>);
+
+/// Counts towards the next power-up appearing.
+#[derive(Debug, Default, Clone)]
+pub struct PowerUpTimer {
+    pub time: f32,
+    /// How many have appeared so far this match.
+    pub spawned: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn win_by_two_plays_on_from_deuce() {
+        let rules = Rules {
+            points_per_game: 3,
+            win_by_two: true,
+            ..Rules::default()
+        };
+        let mut board = ScoreBoard::default();
+
+        for side in [Side::Left, Side::Right, Side::Left, Side::Right, Side::Left].iter() {
+            board.point(*side, &rules);
+        }
+
+        // 3-2 reaches the points needed, but isn't two clear.
+        assert_eq!(board.points, [3, 2, 0, 0]);
+        assert_eq!(board.winner(&rules), None);
+
+        board.point(Side::Right, &rules);
+        board.point(Side::Left, &rules);
+        assert_eq!(board.winner(&rules), None);
+
+        board.point(Side::Left, &rules);
+        assert_eq!(board.winner(&rules), Some(Side::Left));
+        assert_eq!(board.last_game, [5, 3, 0, 0]);
+    }
+
+    #[test]
+    fn games_roll_over_into_sets() {
+        let rules = Rules {
+            points_per_game: 2,
+            games_per_set: 2,
+            sets_to_win: 2,
+            ..Rules::default()
+        };
+        let mut board = ScoreBoard::default();
+        let game = |board: &mut ScoreBoard, side| {
+            board.point(side, &rules);
+            board.point(side, &rules);
+        };
+
+        game(&mut board, Side::Left);
+        assert_eq!((board.points, board.games), ([0; 4], [1, 0, 0, 0]));
+
+        game(&mut board, Side::Right);
+        game(&mut board, Side::Left);
+        assert_eq!(board.sets, [1, 0, 0, 0]);
+        assert_eq!(board.set_scores, vec![[2, 1, 0, 0]]);
+        assert_eq!(board.games, [0; 4]);
+        assert_eq!(board.winner(&rules), None);
+
+        game(&mut board, Side::Left);
+        game(&mut board, Side::Left);
+        assert_eq!(board.winner(&rules), Some(Side::Left));
+
+        // Points after the match is won don't count.
+        board.point(Side::Right, &rules);
+        assert_eq!(board.points, [0; 4]);
+    }
+
+    #[test]
+    fn head_start_carries_into_every_game() {
+        let rules = Rules {
+            points_per_game: 3,
+            games_per_set: 2,
+            win_by_two: false,
+            ..Rules::default()
+        };
+        let mut board = ScoreBoard::new([0, 2, 0, 0]);
+
+        board.point(Side::Right, &rules);
+        assert_eq!(board.games, [0, 1, 0, 0]);
+        assert_eq!(board.last_game, [0, 3, 0, 0]);
+
+        // The next game begins from the head start again, not from nothing.
+        assert_eq!(board.points, [0, 2, 0, 0]);
+    }
+
+    #[test]
+    fn series_goes_to_whoever_wins_most() {
+        let mut series = Series::new(5);
+        assert_eq!(series.to_win(), 3);
+        assert_eq!(Series::new(4).to_win(), 3);
+        assert_eq!(Series::new(1).to_win(), 1);
+
+        for side in [Side::Left, Side::Right, Side::Right, Side::Left].iter() {
+            series.record(*side);
+        }
+        assert_eq!(series.winner(), None);
+        assert_eq!(series.tally(&[Side::Left, Side::Right]), "2-2");
+
+        series.record(Side::Right);
+        assert_eq!(series.winner(), Some(Side::Right));
+        assert_eq!(series.played(), 5);
+    }
+}
@@ -0,0 +1,98 @@
+//! Brick layouts for breakout, loaded from `assets/levels/`.
+
+use crate::arena::Arena;
+use crate::core::Brick;
+use amethyst::assets::{Asset, Handle};
+use amethyst::ecs::VecStorage;
+use serde::{Deserialize, Serialize};
+
+/// A wall of bricks, stacked against the right side of the arena.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Level {
+    /// The full width and height of every brick.
+    pub brick_size: [f32; 2],
+    /// The space left between neighbouring bricks.
+    pub gap: f32,
+    /// How far the wall stands from the right side of the arena.
+    pub margin: f32,
+    /// The bricks, a row of text at a time from the top down. A digit is a
+    /// brick that takes that many hits to break, and anything else is a gap.
+    pub rows: Vec<String>,
+}
+
+impl Default for Level {
+    fn default() -> Level {
+        Level {
+            brick_size: [4.0, 8.0],
+            gap: 1.0,
+            margin: 6.0,
+            rows: Vec::new(),
+        }
+    }
+}
+
+impl Level {
+    /// Every brick of the level and the position of its centre, with the
+    /// wall centred top to bottom in the `arena`.
+    pub fn bricks(&self, arena: &Arena) -> Vec<(Brick, [f32; 2])> {
+        let [width, height] = self.brick_size;
+        let columns = self.rows.iter().map(|row| row.chars().count()).max();
+        let columns = columns.unwrap_or(0) as f32;
+        let step = [width + self.gap, height + self.gap];
+        let left = arena.width - self.margin - columns * step[0] + self.gap;
+        let top = (arena.height + self.rows.len() as f32 * step[1] - self.gap) * 0.5;
+
+        self.rows
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.chars().enumerate().filter_map(move |(x, c)| {
+                    c.to_digit(10).filter(|hits| *hits > 0).map(|hits| {
+                        let centre = [
+                            left + x as f32 * step[0] + width * 0.5,
+                            top - y as f32 * step[1] - height * 0.5,
+                        ];
+                        let brick = Brick {
+                            hits,
+                            size: self.brick_size,
+                        };
+
+                        (brick, centre)
+                    })
+                })
+            })
+            .collect()
+    }
+}
+
+impl Asset for Level {
+    const NAME: &'static str = "pong::Level";
+    type Data = Self;
+    type HandleStorage = VecStorage<Handle<Self>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bricks_stand_against_the_right_wall() {
+        let level = Level {
+            rows: vec!["12".to_string(), "03".to_string()],
+            ..Level::default()
+        };
+        let bricks: Vec<(u32, [f32; 2])> = level
+            .bricks(&Arena::default())
+            .into_iter()
+            .map(|(brick, centre)| (brick.hits, centre))
+            .collect();
+
+        // The last column ends `margin` short of the right wall, and the rows
+        // are centred top to bottom. Zeroes are gaps.
+        assert_eq!(
+            bricks,
+            vec![(1, [87.0, 54.5]), (2, [92.0, 54.5]), (3, [92.0, 45.5])]
+        );
+    }
+}
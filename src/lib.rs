@@ -1,231 +1,1602 @@
+pub mod arena;
 pub mod audio;
+pub mod controls;
 pub mod core;
+pub mod level;
+pub mod replay;
+pub mod rules;
+pub mod sim;
 pub mod systems;
 
+use crate::arena::{Arena, Obstacle};
+use crate::controls::{Slot, UserBindings};
 use crate::core::*;
-use amethyst::assets::{AssetStorage, Handle, Loader};
+use crate::level::Level;
+use crate::replay::{Playback, Recorder, ReplayDir};
+use crate::rules::Rules;
+use amethyst::assets::{AssetStorage, Completion, Handle, Loader, ProgressCounter, RonFormat};
 use amethyst::audio::AudioSink;
+use amethyst::config::Config;
+use amethyst::core::math::Vector3;
+use amethyst::core::timing::Time;
 use amethyst::core::transform::Transform;
 use amethyst::core::ArcThreadPool;
-use amethyst::ecs::{Dispatcher, DispatcherBuilder, Entity};
-use amethyst::input::InputEvent;
+use amethyst::core::Hidden;
+use amethyst::ecs::{Dispatcher, DispatcherBuilder, Entity, Join};
+use amethyst::input::{Button, InputEvent, InputHandler, StringBindings};
 use amethyst::prelude::*;
 use amethyst::renderer::{
     Camera, ImageFormat, SpriteRender, SpriteSheet, SpriteSheetFormat, Texture,
 };
-use amethyst::ui::{Anchor, FontHandle, TtfFormat, UiText, UiTransform};
+use amethyst::ui::{Anchor, FontHandle, LineMode, TtfFormat, UiText, UiTransform};
 use amethyst::utils::fps_counter::FpsCounter;
+use amethyst::winit::VirtualKeyCode;
+use rand::Rng;
 
-/// The initial landing screen.
+/// The first `State`. It sets up what every other state shares, then opens
+/// the main menu.
 #[derive(Default)]
 pub struct Welcome {
     font: Option<FontHandle>,
+}
+
+impl SimpleState for Welcome {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let world = data.world;
+
+        // Read the font.
+        let font: FontHandle = world.read_resource::<Loader>().load(
+            "font/arcade.ttf",
+            TtfFormat,
+            (),
+            &world.read_resource(),
+        );
+        self.font.replace(font);
+
+        initialize_camera(world);
+        audio::initialize_audio(world);
+    }
+
+    fn update(&mut self, _: &mut StateData<GameData>) -> SimpleTrans {
+        match self.font.take() {
+            Some(font) => Trans::Replace(Box::new(MainMenu::new(font))),
+            None => Trans::None,
+        }
+    }
+}
+
+/// The entries of the main menu, from the top down.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Choice {
+    VsHuman,
+    VsComputer,
+    FourPlayers,
+    Series,
+    Practice,
+    Bricks,
+    Replay,
+    Options,
+    Quit,
+}
+
+impl Choice {
+    const ALL: [Choice; 9] = [
+        Choice::VsHuman,
+        Choice::VsComputer,
+        Choice::FourPlayers,
+        Choice::Series,
+        Choice::Practice,
+        Choice::Bricks,
+        Choice::Replay,
+        Choice::Options,
+        Choice::Quit,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Choice::VsHuman => "Play vs Human",
+            Choice::VsComputer => "Play vs AI",
+            Choice::FourPlayers => "Four Players",
+            Choice::Series => "Series",
+            Choice::Practice => "Practice",
+            Choice::Bricks => "Bricks",
+            Choice::Replay => "Watch Replay",
+            Choice::Options => "Options",
+            Choice::Quit => "Quit",
+        }
+    }
+}
+
+/// The landing screen, where a mode of play is chosen with the arrow keys.
+pub struct MainMenu {
+    font: FontHandle,
+    selected: usize,
+    /// One for each `Choice`, in order.
+    entries: Vec<Entity>,
+    /// How to pause and quit, which changes with the bindings.
+    hints: Option<Entity>,
+    entities: Vec<Entity>,
+}
+
+impl MainMenu {
+    pub fn new(font: FontHandle) -> MainMenu {
+        MainMenu {
+            font,
+            selected: 0,
+            entries: vec![],
+            hints: None,
+            entities: vec![],
+        }
+    }
+
+    /// Show how to pause and quit with the bindings as they are now.
+    fn show_hints(&mut self, world: &mut World) {
+        if let Some(hints) = self.hints.take() {
+            let _ = world.delete_entity(hints);
+        }
+
+        let hints = controls::hints(
+            &world
+                .read_resource::<InputHandler<StringBindings>>()
+                .bindings,
+        );
+        let entity = generic_message(
+            world,
+            self.font.clone(),
+            Anchor::BottomMiddle,
+            &hints,
+            Some(25.0),
+        );
+        self.hints.replace(entity);
+    }
+
+    fn choose(&mut self, world: &mut World) -> SimpleTrans {
+        let font = self.font.clone();
+
+        match Choice::ALL[self.selected] {
+            Choice::VsHuman => {
+                Trans::Replace(Box::new(MatchSetup::new(font, Players::two_player())))
+            }
+            Choice::VsComputer => {
+                Trans::Replace(Box::new(MatchSetup::new(font, Players::one_player())))
+            }
+            Choice::FourPlayers => {
+                Trans::Replace(Box::new(MatchSetup::new(font, Players::four_player())))
+            }
+            Choice::Series => Trans::Replace(Box::new(SeriesMenu::new(font))),
+            Choice::Practice => Trans::Replace(Box::new(Pong::new(font, Players::practice()))),
+            Choice::Bricks => Trans::Replace(Box::new(Breakout::new(font))),
+            Choice::Replay => {
+                let path = world.read_resource::<ReplayDir>().last();
+
+                match replay::Replay::load(&path) {
+                    Ok(recording) => Trans::Replace(Box::new(Replay::new(font, recording))),
+                    Err(e) => {
+                        log::warn!("Couldn't load replay {}: {}", path.display(), e);
+                        Trans::None
+                    }
+                }
+            }
+            Choice::Options => Trans::Push(Box::new(Options::new(font))),
+            Choice::Quit => Trans::Quit,
+        }
+    }
+}
+
+impl SimpleState for MainMenu {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let world = data.world;
+
+        self.show_hints(world);
+        let prompt = generic_message(
+            world,
+            self.font.clone(),
+            Anchor::TopMiddle,
+            "Up and Down to Choose, Enter to Play",
+            Some(25.0),
+        );
+        let logo = initialize_logo(world);
+        let labels: Vec<&str> = Choice::ALL.iter().map(|choice| choice.label()).collect();
+        self.entries = initialize_menu(world, self.font.clone(), 10.0, &labels);
+        highlight(world, &self.entries, self.selected);
+
+        self.entities = vec![prompt, logo];
+        self.entities.extend(self.entries.iter().copied());
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        let _ = data.world.delete_entities(&self.entities);
+        self.hints.take().iter().for_each(|entity| {
+            let _ = data.world.delete_entity(*entity);
+        });
+    }
+
+    fn on_pause(&mut self, data: StateData<GameData>) {
+        let mut hiddens = data.world.write_storage::<Hidden>();
+
+        for entity in self.entities.iter().chain(self.hints.iter()) {
+            let _ = hiddens.insert(*entity, Hidden);
+        }
+    }
+
+    fn on_resume(&mut self, data: StateData<GameData>) {
+        {
+            let mut hiddens = data.world.write_storage::<Hidden>();
+
+            for entity in self.entities.iter() {
+                hiddens.remove(*entity);
+            }
+        }
+
+        // The bindings may have changed in the meantime.
+        self.show_hints(data.world);
+    }
+
+    fn handle_event(&mut self, data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        match event {
+            // The footer offers quitting from here, as well as from a match.
+            StateEvent::Input(InputEvent::ActionPressed(a)) if a == "quit" => Trans::Quit,
+            StateEvent::Input(InputEvent::KeyPressed { key_code, .. }) => match key_code {
+                VirtualKeyCode::Up | VirtualKeyCode::W => {
+                    self.selected = select(data.world, &self.entries, self.selected, -1);
+                    Trans::None
+                }
+                VirtualKeyCode::Down | VirtualKeyCode::S => {
+                    self.selected = select(data.world, &self.entries, self.selected, 1);
+                    Trans::None
+                }
+                VirtualKeyCode::Return | VirtualKeyCode::Space => self.choose(data.world),
+                _ => Trans::None,
+            },
+            _ => Trans::None,
+        }
+    }
+}
+
+/// One line of the handicap screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Setup {
+    Play,
+    Length(Side),
+    Speed(Side),
+    HeadStart(Side),
+    Back,
+}
+
+impl Setup {
+    /// Every line for a match between the `players`, from the top down.
+    fn all(players: Players) -> Vec<Setup> {
+        let sides = players.sides();
+        let handicaps = sides.iter().flat_map(|side| {
+            vec![
+                Setup::Length(*side),
+                Setup::Speed(*side),
+                Setup::HeadStart(*side),
+            ]
+        });
+
+        std::iter::once(Setup::Play)
+            .chain(handicaps)
+            .chain(std::iter::once(Setup::Back))
+            .collect()
+    }
+
+    fn label(self, rules: &Rules) -> String {
+        let handicap = |side| rules.handicaps.for_side(side);
+
+        match self {
+            Setup::Play => "Play".to_string(),
+            Setup::Length(side) => {
+                format!(
+                    "{} Length {:.2}",
+                    side.player(),
+                    handicap(side).paddle_length
+                )
+            }
+            Setup::Speed(side) => {
+                format!("{} Speed {:.2}", side.player(), handicap(side).paddle_speed)
+            }
+            Setup::HeadStart(side) => {
+                format!("{} Head Start {}", side.player(), handicap(side).points)
+            }
+            Setup::Back => "Back".to_string(),
+        }
+    }
+
+    /// Raise or lower the handicap on this line by `by` notches.
+    fn adjust(self, rules: &mut Rules, by: i32) {
+        let most = rules.points_per_game - 1;
+        let notch = |value: f32| (value + by as f32 * 0.25).clamp(0.5, 2.0);
+
+        match self {
+            Setup::Length(side) => {
+                let handicap = rules.handicaps.for_side_mut(side);
+                handicap.paddle_length = notch(handicap.paddle_length);
+            }
+            Setup::Speed(side) => {
+                let handicap = rules.handicaps.for_side_mut(side);
+                handicap.paddle_speed = notch(handicap.paddle_speed);
+            }
+            Setup::HeadStart(side) => {
+                let handicap = rules.handicaps.for_side_mut(side);
+                handicap.points = (handicap.points as i32 + by).clamp(0, most as i32) as u32;
+            }
+            Setup::Play | Setup::Back => {}
+        }
+    }
+}
+
+/// The last word before a match, where each player's handicap is set with
+/// the left and right keys.
+pub struct MatchSetup {
+    font: FontHandle,
+    players: Players,
+    selected: usize,
+    /// One for each `Setup` line.
+    entries: Vec<Entity>,
+    entities: Vec<Entity>,
+}
+
+impl MatchSetup {
+    pub fn new(font: FontHandle, players: Players) -> MatchSetup {
+        MatchSetup {
+            font,
+            players,
+            selected: 0,
+            entries: vec![],
+            entities: vec![],
+        }
+    }
+
+    fn adjust(&mut self, world: &mut World, by: i32) {
+        let line = Setup::all(self.players)[self.selected];
+        let label = {
+            let mut rules = world.write_resource::<Rules>();
+            line.adjust(&mut rules, by);
+            line.label(&rules)
+        };
+
+        if let Some(text) = world
+            .write_storage::<UiText>()
+            .get_mut(self.entries[self.selected])
+        {
+            text.text = label;
+        }
+    }
+
+    fn choose(&mut self, world: &mut World) -> SimpleTrans {
+        let font = self.font.clone();
+
+        match Setup::all(self.players)[self.selected] {
+            Setup::Play => Trans::Replace(Box::new(Pong::new(font, self.players))),
+            Setup::Back => {
+                // A series chosen on the way here isn't begun after all.
+                world.remove::<Series>();
+                Trans::Replace(Box::new(MainMenu::new(font)))
+            }
+            _ => Trans::None,
+        }
+    }
+}
+
+impl SimpleState for MatchSetup {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let world = data.world;
+        let labels: Vec<String> = {
+            let rules = world.read_resource::<Rules>();
+            Setup::all(self.players)
+                .iter()
+                .map(|line| line.label(&rules))
+                .collect()
+        };
+        let labels: Vec<&str> = labels.iter().map(|label| label.as_str()).collect();
+
+        let top = (labels.len() - 1) as f32 * 14.0;
+        self.entries = initialize_menu(world, self.font.clone(), top, &labels);
+        highlight(world, &self.entries, self.selected);
+
+        let prompt = generic_message(
+            world,
+            self.font.clone(),
+            Anchor::TopMiddle,
+            "Left and Right to Set Handicaps",
+            Some(20.0),
+        );
+        self.entities = vec![prompt];
+        self.entities.extend(self.entries.iter().copied());
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        let _ = data.world.delete_entities(&self.entities);
+    }
+
+    fn handle_event(&mut self, data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        match event {
+            StateEvent::Input(InputEvent::KeyPressed { key_code, .. }) => match key_code {
+                VirtualKeyCode::Up | VirtualKeyCode::W => {
+                    self.selected = select(data.world, &self.entries, self.selected, -1);
+                    Trans::None
+                }
+                VirtualKeyCode::Down | VirtualKeyCode::S => {
+                    self.selected = select(data.world, &self.entries, self.selected, 1);
+                    Trans::None
+                }
+                VirtualKeyCode::Left | VirtualKeyCode::A => {
+                    self.adjust(data.world, -1);
+                    Trans::None
+                }
+                VirtualKeyCode::Right | VirtualKeyCode::D => {
+                    self.adjust(data.world, 1);
+                    Trans::None
+                }
+                VirtualKeyCode::Return | VirtualKeyCode::Space => self.choose(data.world),
+                _ => Trans::None,
+            },
+            _ => Trans::None,
+        }
+    }
+}
+
+/// How long a series can run, in matches.
+const SERIES_LENGTHS: [u32; 3] = [3, 5, 7];
+
+/// Where the length of a series is chosen, and who it's played against.
+pub struct SeriesMenu {
+    font: FontHandle,
+    selected: usize,
+    /// One for each of the `series`, then "Back".
+    entries: Vec<Entity>,
+    entities: Vec<Entity>,
+}
+
+impl SeriesMenu {
+    pub fn new(font: FontHandle) -> SeriesMenu {
+        SeriesMenu {
+            font,
+            selected: 0,
+            entries: vec![],
+            entities: vec![],
+        }
+    }
+
+    /// Every series on offer: its length, who plays it, and its name.
+    fn series() -> Vec<(u32, Players, String)> {
+        let opponents = [
+            (Players::one_player(), "AI"),
+            (Players::two_player(), "Human"),
+        ];
+
+        opponents
+            .iter()
+            .flat_map(|(players, name)| {
+                SERIES_LENGTHS
+                    .iter()
+                    .map(move |n| (*n, *players, format!("Best of {} vs {}", n, name)))
+            })
+            .collect()
+    }
+
+    fn choose(&mut self, world: &mut World) -> SimpleTrans {
+        let font = self.font.clone();
+
+        match SeriesMenu::series().get(self.selected) {
+            Some((best_of, players, _)) => {
+                world.insert(Series::new(*best_of));
+                Trans::Replace(Box::new(MatchSetup::new(font, *players)))
+            }
+            None => Trans::Replace(Box::new(MainMenu::new(font))),
+        }
+    }
+}
+
+impl SimpleState for SeriesMenu {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let world = data.world;
+        let mut labels: Vec<String> = SeriesMenu::series()
+            .into_iter()
+            .map(|(_, _, label)| label)
+            .collect();
+        labels.push("Back".to_string());
+        let labels: Vec<&str> = labels.iter().map(|label| label.as_str()).collect();
+
+        let top = (labels.len() - 1) as f32 * 14.0;
+        self.entries = initialize_menu(world, self.font.clone(), top, &labels);
+        highlight(world, &self.entries, self.selected);
+
+        let prompt = generic_message(
+            world,
+            self.font.clone(),
+            Anchor::TopMiddle,
+            "Up and Down to Choose, Enter to Play",
+            Some(25.0),
+        );
+        self.entities = vec![prompt];
+        self.entities.extend(self.entries.iter().copied());
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        let _ = data.world.delete_entities(&self.entities);
+    }
+
+    fn handle_event(&mut self, data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        match event {
+            StateEvent::Input(InputEvent::KeyPressed { key_code, .. }) => match key_code {
+                VirtualKeyCode::Up | VirtualKeyCode::W => {
+                    self.selected = select(data.world, &self.entries, self.selected, -1);
+                    Trans::None
+                }
+                VirtualKeyCode::Down | VirtualKeyCode::S => {
+                    self.selected = select(data.world, &self.entries, self.selected, 1);
+                    Trans::None
+                }
+                VirtualKeyCode::Return | VirtualKeyCode::Space => self.choose(data.world),
+                _ => Trans::None,
+            },
+            _ => Trans::None,
+        }
+    }
+}
+
+/// The controls of every player. Choosing one waits for the next key pressed,
+/// which takes its place straight away. Changes last for the session unless
+/// saved.
+pub struct Options {
+    font: FontHandle,
+    selected: usize,
+    /// The slot waiting on a key, if any.
+    capturing: Option<Slot>,
+    /// One for each `Slot`, then "Save" and "Back".
+    entries: Vec<Entity>,
+    status: Option<Entity>,
+    entities: Vec<Entity>,
+}
+
+impl Options {
+    pub fn new(font: FontHandle) -> Options {
+        Options {
+            font,
+            selected: 0,
+            capturing: None,
+            entries: vec![],
+            status: None,
+            entities: vec![],
+        }
+    }
+
+    fn choose(&mut self, world: &mut World) -> SimpleTrans {
+        let slots = Slot::all();
+
+        match slots.get(self.selected) {
+            Some(slot) => {
+                self.capturing = Some(*slot);
+                let message = format!("Press a key for {}", slot.name());
+                self.show_status(world, &message);
+            }
+            None if self.selected == slots.len() => {
+                let result = {
+                    let input = world.read_resource::<InputHandler<StringBindings>>();
+                    let file = world.read_resource::<UserBindings>();
+                    file.save(&input.bindings).map_err(|e| {
+                        log::warn!("Couldn't save bindings {}: {}", file.path().display(), e);
+                    })
+                };
+
+                match result {
+                    Ok(_) => self.show_status(world, "Saved"),
+                    Err(_) => self.show_status(world, "Couldn't save"),
+                }
+            }
+            None => return Trans::Pop,
+        }
+
+        Trans::None
+    }
+
+    /// Bind the key just pressed to the slot waiting on one.
+    fn capture(&mut self, world: &mut World, slot: Slot, key: VirtualKeyCode) {
+        self.capturing = None;
+
+        if key == VirtualKeyCode::Back {
+            self.show_status(world, "");
+            return;
+        }
+
+        let result = {
+            let mut input = world.write_resource::<InputHandler<StringBindings>>();
+            controls::rebind(&mut input.bindings, slot, Button::Key(key))
+                .map(|_| slot.describe(&input.bindings))
+        };
+
+        match result {
+            Ok(line) => {
+                if let Some(text) = world
+                    .write_storage::<UiText>()
+                    .get_mut(self.entries[self.selected])
+                {
+                    text.text = line;
+                }
+                self.show_status(world, "");
+            }
+            Err(e) => self.show_status(world, &e),
+        }
+    }
+
+    fn show_status(&mut self, world: &mut World, message: &str) {
+        if let Some(status) = self.status.take() {
+            let _ = world.delete_entity(status);
+        }
+
+        if !message.is_empty() {
+            let entity = generic_message(
+                world,
+                self.font.clone(),
+                Anchor::BottomMiddle,
+                message,
+                Some(20.0),
+            );
+            self.status.replace(entity);
+        }
+    }
+}
+
+impl SimpleState for Options {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let world = data.world;
+        let mut lines: Vec<String> = {
+            let input = world.read_resource::<InputHandler<StringBindings>>();
+            Slot::all()
+                .iter()
+                .map(|slot| slot.describe(&input.bindings))
+                .collect()
+        };
+        lines.push("Save".to_string());
+        lines.push("Back".to_string());
+        let lines: Vec<&str> = lines.iter().map(|line| line.as_str()).collect();
+
+        // The list is taller than the main menu, and has no logo to make room for.
+        let top = (lines.len() - 1) as f32 * 14.0;
+        self.entries = initialize_menu(world, self.font.clone(), top, &lines);
+        highlight(world, &self.entries, self.selected);
+
+        let prompt = generic_message(
+            world,
+            self.font.clone(),
+            Anchor::TopMiddle,
+            "Enter to Change, Backspace to Cancel",
+            Some(20.0),
+        );
+        self.entities = vec![prompt];
+        self.entities.extend(self.entries.iter().copied());
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        let _ = data.world.delete_entities(&self.entities);
+        self.show_status(data.world, "");
+    }
+
+    fn handle_event(&mut self, data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        match event {
+            StateEvent::Input(InputEvent::KeyPressed { key_code, .. }) => match self.capturing {
+                Some(slot) => {
+                    self.capture(data.world, slot, key_code);
+                    Trans::None
+                }
+                None => match key_code {
+                    VirtualKeyCode::Up | VirtualKeyCode::W => {
+                        self.selected = select(data.world, &self.entries, self.selected, -1);
+                        Trans::None
+                    }
+                    VirtualKeyCode::Down | VirtualKeyCode::S => {
+                        self.selected = select(data.world, &self.entries, self.selected, 1);
+                        Trans::None
+                    }
+                    VirtualKeyCode::Return | VirtualKeyCode::Space => self.choose(data.world),
+                    VirtualKeyCode::Back => Trans::Pop,
+                    _ => Trans::None,
+                },
+            },
+            _ => Trans::None,
+        }
+    }
+}
+
+/// The entries of the game-over menu, from the top down.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AfterMatch {
+    Rematch,
+    MainMenu,
+    Quit,
+}
+
+impl AfterMatch {
+    const ALL: [AfterMatch; 3] = [AfterMatch::Rematch, AfterMatch::MainMenu, AfterMatch::Quit];
+
+    fn label(self) -> &'static str {
+        match self {
+            AfterMatch::Rematch => "Rematch",
+            AfterMatch::MainMenu => "Main Menu",
+            AfterMatch::Quit => "Quit",
+        }
+    }
+}
+
+/// How a match turned out, and what to play next.
+pub struct GameOver {
+    font: FontHandle,
+    result: MatchResult,
+    /// Who played the match, to play it again.
+    players: Players,
+    /// The series the match decided, if it was part of one.
+    series: Option<Series>,
+    selected: usize,
+    entries: Vec<Entity>,
+    entities: Vec<Entity>,
+}
+
+impl GameOver {
+    fn new(font: FontHandle, result: MatchResult, players: Players) -> GameOver {
+        GameOver {
+            font,
+            result,
+            players,
+            series: None,
+            selected: 0,
+            entries: vec![],
+            entities: vec![],
+        }
+    }
+
+    /// The final score and the highlights of the match.
+    fn stats(&self, rules: &Rules) -> Vec<String> {
+        let seconds = self.result.duration.round() as u32;
+        let series = self
+            .series
+            .map(|series| format!("Series {}", series.tally(&self.players.sides())));
+
+        series
+            .into_iter()
+            .chain(vec![
+                self.result.summary(rules, &self.players.sides()),
+                format!("Longest Rally {}", self.result.longest_rally),
+                format!("Time {}:{:02}", seconds / 60, seconds % 60),
+                format!("Top Speed {:.0}", self.result.top_speed),
+            ])
+            .collect()
+    }
+
+    fn choose(&mut self, world: &mut World) -> SimpleTrans {
+        let font = self.font.clone();
+
+        match AfterMatch::ALL[self.selected] {
+            AfterMatch::Rematch => {
+                // A series is played again from the start.
+                if let Some(series) = self.series {
+                    world.insert(Series::new(series.best_of));
+                }
+
+                Trans::Replace(Box::new(Pong::new(font, self.players)))
+            }
+            AfterMatch::MainMenu => Trans::Replace(Box::new(MainMenu::new(font))),
+            AfterMatch::Quit => Trans::Quit,
+        }
+    }
+}
+
+impl SimpleState for GameOver {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let world = data.world;
+        // The series is over, so it's no longer kept for the next match.
+        self.series = world.remove::<Series>();
+        let winner = self.result.winner.player();
+        let (title, size) = match self.series {
+            Some(_) => (format!("{} Takes the Series", winner), Some(35.0)),
+            None => (format!("{} Wins", winner), None),
+        };
+        let stats = self.stats(&world.read_resource::<Rules>());
+        let stats: Vec<&str> = stats.iter().map(|line| line.as_str()).collect();
+
+        let title = generic_message(world, self.font.clone(), Anchor::TopMiddle, &title, size);
+        let stats = initialize_menu(world, self.font.clone(), 150.0, &stats);
+        let labels: Vec<&str> = AfterMatch::ALL.iter().map(|a| a.label()).collect();
+        self.entries = initialize_menu(world, self.font.clone(), -30.0, &labels);
+        highlight(world, &self.entries, self.selected);
+
+        self.entities = vec![title];
+        self.entities.extend(stats);
+        self.entities.extend(self.entries.iter().copied());
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        let _ = data.world.delete_entities(&self.entities);
+    }
+
+    fn handle_event(&mut self, data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        match event {
+            StateEvent::Input(InputEvent::KeyPressed { key_code, .. }) => match key_code {
+                VirtualKeyCode::Up | VirtualKeyCode::W => {
+                    self.selected = select(data.world, &self.entries, self.selected, -1);
+                    Trans::None
+                }
+                VirtualKeyCode::Down | VirtualKeyCode::S => {
+                    self.selected = select(data.world, &self.entries, self.selected, 1);
+                    Trans::None
+                }
+                VirtualKeyCode::Return | VirtualKeyCode::Space => self.choose(data.world),
+                _ => Trans::None,
+            },
+            _ => Trans::None,
+        }
+    }
+}
+
+/// A word between the matches of a series, with the tally so far. Any key
+/// plays the next match.
+pub struct Standings {
+    font: FontHandle,
+    /// The winner of the match just played.
+    winner: Side,
+    players: Players,
+    entities: Vec<Entity>,
+}
+
+impl SimpleState for Standings {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let world = data.world;
+        let series = *world.read_resource::<Series>();
+        let title = format!("{} Wins Match {}", self.winner.player(), series.played());
+        let lines = [
+            format!("Series {}", series.tally(&self.players.sides())),
+            format!("First to {}", series.to_win()),
+        ];
+        let lines: Vec<&str> = lines.iter().map(|line| line.as_str()).collect();
+
+        let title = generic_message(
+            world,
+            self.font.clone(),
+            Anchor::TopMiddle,
+            &title,
+            Some(35.0),
+        );
+        let prompt = generic_message(
+            world,
+            self.font.clone(),
+            Anchor::BottomMiddle,
+            "Press any key",
+            Some(25.0),
+        );
+        self.entities = initialize_menu(world, self.font.clone(), 10.0, &lines);
+        self.entities.extend(vec![title, prompt]);
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        let _ = data.world.delete_entities(&self.entities);
+    }
+
+    fn handle_event(&mut self, _: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        match event {
+            StateEvent::Input(InputEvent::KeyPressed { .. }) => {
+                Trans::Replace(Box::new(Pong::new(self.font.clone(), self.players)))
+            }
+            _ => Trans::None,
+        }
+    }
+}
+
+/// The entries of the pause menu, from the top down.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Paused {
+    Resume,
+    Restart,
+    Options,
+    MainMenu,
+    Quit,
+}
+
+impl Paused {
+    const ALL: [Paused; 5] = [
+        Paused::Resume,
+        Paused::Restart,
+        Paused::Options,
+        Paused::MainMenu,
+        Paused::Quit,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Paused::Resume => "Resume",
+            Paused::Restart => "Restart",
+            Paused::Options => "Options",
+            Paused::MainMenu => "Main Menu",
+            Paused::Quit => "Quit",
+        }
+    }
+}
+
+/// The "paused" `State`, with a menu to carry on, start over or leave.
+pub struct Pause {
+    font: FontHandle,
+    /// A fresh start of the paused state, which replaces it on restart.
+    restart: Box<dyn Fn() -> SimpleTrans>,
+    selected: usize,
+    entries: Vec<Entity>,
     entities: Vec<Entity>,
 }
 
-impl SimpleState for Welcome {
+impl Pause {
+    fn new(font: FontHandle, restart: Box<dyn Fn() -> SimpleTrans>) -> Pause {
+        Pause {
+            font,
+            restart,
+            selected: 0,
+            entries: vec![],
+            entities: vec![],
+        }
+    }
+
+    fn choose(&mut self, world: &mut World) -> SimpleTrans {
+        match Paused::ALL[self.selected] {
+            Paused::Resume => Trans::Pop,
+            Paused::Restart => {
+                // The abandoned match isn't kept as a replay, and the new one
+                // begins from the same seed, as if it had never been played.
+                if let Some(Recorder(recording)) = world.remove::<Recorder>() {
+                    world.insert(Random::new(recording.seed));
+                }
+
+                Trans::Sequence(vec![Trans::Pop, (self.restart)()])
+            }
+            Paused::Options => Trans::Push(Box::new(Options::new(self.font.clone()))),
+            Paused::MainMenu => {
+                // Leaving partway through a series abandons it.
+                world.remove::<Series>();
+                let menu = MainMenu::new(self.font.clone());
+                Trans::Sequence(vec![Trans::Pop, Trans::Replace(Box::new(menu))])
+            }
+            Paused::Quit => Trans::Quit,
+        }
+    }
+}
+
+impl SimpleState for Pause {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let world = data.world;
+        let title = initialize_pause_message(world, self.font.clone());
+        let labels: Vec<&str> = Paused::ALL.iter().map(|p| p.label()).collect();
+        self.entries = initialize_menu(world, self.font.clone(), -40.0, &labels);
+        highlight(world, &self.entries, self.selected);
+        self.entities = vec![title];
+        self.entities.extend(self.entries.iter().copied());
+        world.read_resource::<AudioSink>().pause();
+    }
+
+    fn on_pause(&mut self, data: StateData<GameData>) {
+        let mut hiddens = data.world.write_storage::<Hidden>();
+
+        for entity in self.entities.iter() {
+            let _ = hiddens.insert(*entity, Hidden);
+        }
+    }
+
+    fn on_resume(&mut self, data: StateData<GameData>) {
+        let mut hiddens = data.world.write_storage::<Hidden>();
+
+        for entity in self.entities.iter() {
+            hiddens.remove(*entity);
+        }
+    }
+
+    fn handle_event(&mut self, data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        match event {
+            StateEvent::Input(InputEvent::ActionPressed(a)) if a == "quit" => Trans::Quit,
+            StateEvent::Input(InputEvent::ActionPressed(a)) if a == "pause" => Trans::Pop,
+            StateEvent::Input(InputEvent::KeyPressed { key_code, .. }) => match key_code {
+                VirtualKeyCode::Up | VirtualKeyCode::W => {
+                    self.selected = select(data.world, &self.entries, self.selected, -1);
+                    Trans::None
+                }
+                VirtualKeyCode::Down | VirtualKeyCode::S => {
+                    self.selected = select(data.world, &self.entries, self.selected, 1);
+                    Trans::None
+                }
+                VirtualKeyCode::Return | VirtualKeyCode::Space => self.choose(data.world),
+                _ => Trans::None,
+            },
+            _ => Trans::None,
+        }
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        data.world.read_resource::<AudioSink>().play();
+        let _ = data.world.delete_entities(&self.entities);
+    }
+}
+
+/// The main game `State`.
+pub struct Pong<'a, 'b> {
+    sprite_sheet: Option<Handle<SpriteSheet>>,
+    /// The court being loaded. Play begins once it's ready.
+    arena: Option<Handle<Arena>>,
+    progress: ProgressCounter,
+    ready: bool,
+    dispatcher: Option<Dispatcher<'a, 'b>>,
+    font: FontHandle,
+    players: Players,
+    /// Whether to keep a recording of the match, to be replayed later.
+    record: bool,
+    entities: Vec<Entity>,
+    fps: Option<Entity>,
+}
+
+impl<'a, 'b> Pong<'a, 'b> {
+    pub fn new(font: FontHandle, players: Players) -> Pong<'a, 'b> {
+        Pong {
+            sprite_sheet: None,
+            arena: None,
+            progress: ProgressCounter::new(),
+            ready: false,
+            dispatcher: None,
+            font,
+            players,
+            record: true,
+            entities: vec![],
+            fps: None,
+        }
+    }
+
+    /// Create all entities, laid out on the loaded `Arena`.
+    fn initialize_entities(&mut self, world: &mut World) {
+        let sprite_sheet = self.sprite_sheet.clone().unwrap();
+
+        let (width, height) = {
+            let arena = world.read_resource::<Arena>();
+            (arena.width, arena.height)
+        };
+        fit_camera(world, width, height);
+
+        let paddles = initialize_paddles(world, sprite_sheet.clone(), self.players);
+        let obstacles = initialize_obstacles(world, sprite_sheet.clone(), self.players);
+        let hud = initialize_scoreboard(world, self.font.clone(), self.players);
+        initialize_balls(world, sprite_sheet, self.players);
+        let ready = initialize_ready_msg(world, self.font.clone());
+        let fps = initialize_fps(world, self.font.clone());
+        let mut entities = paddles;
+        entities.extend(obstacles);
+        entities.extend(hud);
+        entities.extend(vec![ready, fps]);
+        self.entities = entities;
+        self.fps = Some(fps);
+    }
+}
+
+impl<'a, 'b> SimpleState for Pong<'a, 'b> {
+    fn on_start(&mut self, data: StateData<GameData>) {
+        let world = data.world;
+
+        // Initial the system dispatcher unique to the "running" game state.
+        let mut builder = DispatcherBuilder::new();
+        // The systems run in the same order as `PongSim::step`, so that a
+        // match plays out here exactly as it does headless.
+        builder.add(systems::EffectSystem, "effect_system", &[]);
+        builder.add(systems::PaddleSystem, "paddle_system", &["effect_system"]);
+        builder.add(systems::ServeSystem, "serve_system", &["paddle_system"]);
+        builder.add(
+            systems::MoveBallSystem,
+            "ball_system",
+            &["paddle_system", "serve_system"],
+        );
+        builder.add(systems::BounceSystem, "collision_system", &["ball_system"]);
+        builder.add(
+            systems::CollectPowerUpSystem,
+            "collect_power_up_system",
+            &["collision_system"],
+        );
+        builder.add(
+            systems::ScoreSystem,
+            "score_system",
+            &["collect_power_up_system"],
+        );
+        builder.add(
+            systems::SpawnBallSystem,
+            "spawn_ball_system",
+            &["score_system"],
+        );
+        builder.add(
+            systems::SpawnPowerUpSystem,
+            "spawn_power_up_system",
+            &["spawn_ball_system"],
+        );
+        builder.add(systems::BrickSystem, "brick_system", &["collision_system"]);
+        builder.add(
+            systems::RallySystem,
+            "rally_system",
+            &["collision_system", "score_system"],
+        );
+        builder.add(systems::FpsSystem, "fps_system", &[]);
+
+        let mut dispatcher = builder
+            .with_pool((*world.read_resource::<ArcThreadPool>()).clone())
+            .build();
+        dispatcher.setup(world);
+        self.dispatcher = Some(dispatcher);
+
+        // Start from a clean slate, in case an earlier match left anything behind.
+        let head_start = world
+            .read_resource::<Rules>()
+            .handicaps
+            .head_start(&self.players.sides());
+        world.insert(ScoreBoard::new(head_start));
+        world.insert(Rally::default());
+        world.insert(Effects::default());
+        world.insert(PowerUpTimer::default());
+        let step = FixedStep::new(&world.read_resource::<Rules>());
+        world.insert(step);
+
+        // Draw from the seed afresh, whatever an unrecorded game before this
+        // one left of it, so that the seed alone is enough to replay the match.
+        let seed = world.read_resource::<Random>().seed;
+        world.insert(Random::new(seed));
+
+        // Record the match, unless it's a replay already.
+        if self.record && !world.has_value::<Playback>() {
+            let rules = (*world.read_resource::<Rules>()).clone();
+            world.insert(Recorder(replay::Replay::new(rules, self.players, seed)));
+        }
+
+        // Set up the sprites.
+        let sprite_sheet_handle = load_sprite_sheet(world);
+        self.sprite_sheet.replace(sprite_sheet_handle);
+
+        // Everything else is laid out on the court, once it has loaded.
+        let arena = load_arena(world, &mut self.progress);
+        self.arena.replace(arena);
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        // This state will never be used again, so we remove all of its entities.
+        // Balls and power-ups come and go during a match, so they're found afresh.
+        let comings_and_goings: Vec<Entity> = {
+            let entities = data.world.entities();
+            let balls = data.world.read_storage::<Ball>();
+            let power_ups = data.world.read_storage::<PowerUp>();
+            (&entities, &balls)
+                .join()
+                .map(|(e, _)| e)
+                .chain((&entities, &power_ups).join().map(|(e, _)| e))
+                .collect()
+        };
+        let _ = data.world.delete_entities(&self.entities);
+        let _ = data.world.delete_entities(&comings_and_goings);
+        data.world.remove::<RallyText>();
+        fit_camera(data.world, ARENA_WIDTH, ARENA_HEIGHT);
+
+        // Keep the recording of the match just played.
+        if let Some(Recorder(recording)) = data.world.remove::<Recorder>() {
+            let path = data.world.read_resource::<ReplayDir>().last();
+
+            if let Err(e) = recording.save(&path) {
+                log::warn!("Couldn't save replay {}: {}", path.display(), e);
+            }
+
+            // The next match gets a seed of its own, drawn from this one.
+            let seed = data.world.write_resource::<Random>().rng.gen();
+            data.world.insert(Random::new(seed));
+        }
+    }
+
+    fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
+        if !self.ready {
+            let arena = match self.progress.complete() {
+                Completion::Loading => return Trans::None,
+                Completion::Complete => {
+                    let storage = data.world.read_resource::<AssetStorage<Arena>>();
+                    self.arena.as_ref().and_then(|h| storage.get(h)).cloned()
+                }
+                Completion::Failed => None,
+            };
+            let arena = arena.filter(|arena| match arena.validate() {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("{}", e);
+                    false
+                }
+            });
+            let arena = arena.unwrap_or_else(|| {
+                let name = &data.world.read_resource::<Rules>().arena;
+                log::warn!(
+                    "Couldn't load arena `{}`, playing on the classic court",
+                    name
+                );
+                Arena::default()
+            });
+            data.world.insert(arena);
+            self.initialize_entities(data.world);
+            self.ready = true;
+        }
+
+        // Special scope to make the borrowed `score_board` disappear as soon as
+        // it's no longer needed. The dispatch below will invoke a system that
+        // wants to borrow the `ScoreBoard` too, which causes a panic.
+        {
+            let score_board = data.world.read_resource::<ScoreBoard>();
+            let rules = data.world.read_resource::<Rules>();
+
+            if let Some(result) = score_board.result(&rules) {
+                let font = self.font.clone();
+                let winner = result.winner;
+                let series = data.world.try_fetch_mut::<Series>().map(|mut series| {
+                    series.record(winner);
+                    series.winner()
+                });
+
+                // In a series, every match but the last leads on to the next.
+                return match series {
+                    Some(None) => Trans::Replace(Box::new(Standings {
+                        font,
+                        winner,
+                        players: self.players,
+                        entities: vec![],
+                    })),
+                    _ => Trans::Replace(Box::new(GameOver::new(font, result, self.players))),
+                };
+            }
+        }
+
+        // Run all `Systems` unique to this `State`, once for every step that
+        // has come due since the last frame.
+        if let Some(dispatcher) = self.dispatcher.as_mut() {
+            let frame = data.world.read_resource::<Time>().delta_seconds();
+            data.world.write_resource::<FixedStep>().add(frame);
+            restore_positions(data.world);
+
+            while data.world.write_resource::<FixedStep>().take() {
+                dispatcher.dispatch(data.world);
+                // Anything deleted during the step must be gone before the next.
+                data.world.maintain();
+                track_positions(data.world);
+            }
+
+            let alpha = data.world.read_resource::<FixedStep>().alpha();
+            blend_positions(data.world, alpha);
+        }
+
+        Trans::None
+    }
+
+    fn handle_event(&mut self, _: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+        match event {
+            StateEvent::Input(InputEvent::ActionPressed(a)) if a == "quit" => Trans::Quit,
+            StateEvent::Input(InputEvent::ActionPressed(a)) if a == "pause" => {
+                let (font, players) = (self.font.clone(), self.players);
+                let restart = move || Trans::Replace(Box::new(Pong::new(font.clone(), players)));
+                Trans::Push(Box::new(Pause::new(self.font.clone(), Box::new(restart))))
+            }
+            _ => Trans::None,
+        }
+    }
+}
+
+/// Breakout: practice against a wall of bricks, with lives in place of
+/// points. Each level follows on from the last until the rules run out of
+/// them.
+pub struct Breakout<'a, 'b> {
+    pong: Pong<'a, 'b>,
+    /// Which of the rules' levels this is.
+    level: usize,
+    /// The lives carried over from the last level, if any.
+    lives: Option<u32>,
+    /// The level being loaded. Its bricks are laid once it's ready.
+    handle: Option<Handle<Level>>,
+    progress: ProgressCounter,
+    laid: bool,
+    entities: Vec<Entity>,
+}
+
+impl<'a, 'b> Breakout<'a, 'b> {
+    /// The first level, with a full set of lives.
+    pub fn new(font: FontHandle) -> Breakout<'a, 'b> {
+        Breakout::at_level(font, 0, None)
+    }
+
+    fn at_level(font: FontHandle, level: usize, lives: Option<u32>) -> Breakout<'a, 'b> {
+        let mut pong = Pong::new(font, Players::practice());
+        // Replays know nothing of bricks, so breakout isn't recorded.
+        pong.record = false;
+
+        Breakout {
+            pong,
+            level,
+            lives,
+            handle: None,
+            progress: ProgressCounter::new(),
+            laid: false,
+            entities: vec![],
+        }
+    }
+
+    /// Lay the bricks of the loaded level, once there's an arena to lay them in.
+    fn lay_bricks(&mut self, world: &mut World) -> Result<(), String> {
+        let bricks = {
+            let storage = world.read_resource::<AssetStorage<Level>>();
+            let level = self
+                .handle
+                .as_ref()
+                .and_then(|handle| storage.get(handle))
+                .ok_or_else(|| format!("Couldn't load level {}", self.level + 1))?;
+            level.bricks(&world.read_resource::<Arena>())
+        };
+        let sprite_sheet = self.pong.sprite_sheet.clone().unwrap();
+        initialize_bricks(world, sprite_sheet, bricks);
+        self.laid = true;
+        Ok(())
+    }
+}
+
+impl<'a, 'b> SimpleState for Breakout<'a, 'b> {
     fn on_start(&mut self, data: StateData<GameData>) {
-        let world = data.world;
+        let (lives, name) = {
+            let rules = data.world.read_resource::<Rules>();
+            let lives = self.lives.unwrap_or(rules.breakout.lives);
+            (lives, rules.breakout.levels[self.level].clone())
+        };
+        data.world.insert(Lives(lives));
 
-        // Read the font.
-        let font: FontHandle = world.read_resource::<Loader>().load(
-            "font/arcade.ttf",
-            TtfFormat,
-            (),
-            &world.read_resource(),
+        let handle = data.world.read_resource::<Loader>().load(
+            format!("levels/{}.ron", name),
+            RonFormat,
+            &mut self.progress,
+            &data.world.read_resource::<AssetStorage<Level>>(),
         );
-        self.font.replace(font);
+        self.handle.replace(handle);
 
-        let instructions = generic_message(
-            world,
-            self.font.clone().unwrap(),
-            Anchor::BottomMiddle,
-            "Esc to Pause, Q to Quit",
-            Some(25.0),
-        );
-        let logo = initialize_logo(world);
-        self.entities = vec![instructions, logo];
+        let text = initialize_lives(data.world, self.pong.font.clone(), lives);
+        self.entities.push(text);
 
-        initialize_camera(world);
-        audio::initialize_audio(world);
+        SimpleState::on_start(&mut self.pong, data);
     }
 
     fn on_stop(&mut self, data: StateData<GameData>) {
+        // Bricks break during play, so the ones left are found afresh.
+        let bricks: Vec<Entity> = {
+            let entities = data.world.entities();
+            let bricks = data.world.read_storage::<Brick>();
+            (&entities, &bricks).join().map(|(e, _)| e).collect()
+        };
+        let _ = data.world.delete_entities(&bricks);
         let _ = data.world.delete_entities(&self.entities);
+        data.world.remove::<Lives>();
+        data.world.remove::<LivesText>();
+        SimpleState::on_stop(&mut self.pong, data);
     }
 
-    fn handle_event(&mut self, _: StateData<GameData>, event: StateEvent) -> SimpleTrans {
-        match event {
-            StateEvent::Input(InputEvent::KeyPressed { .. }) => self
-                .font
-                .as_ref()
-                .map(|font| Trans::Replace(Box::new(Pong::new(font.clone()))))
-                .unwrap_or(Trans::None),
-            _ => Trans::None,
+    fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
+        let trans = SimpleState::update(&mut self.pong, data);
+        let font = self.pong.font.clone();
+
+        if !self.laid {
+            if self.pong.ready && self.progress.complete() != Completion::Loading {
+                if let Err(e) = self.lay_bricks(data.world) {
+                    log::error!("{}", e);
+                    let message = format!("Level {} Missing", self.level + 1);
+                    return Trans::Replace(Box::new(Intermission::new(font, &message, None)));
+                }
+            }
+
+            return trans;
         }
-    }
-}
 
-/// The final `State` before the game exits.
-pub struct GameOver {
-    font: FontHandle,
-}
+        let lives = data.world.read_resource::<Lives>().0;
+        let bricks = data.world.read_storage::<Brick>().join().count();
+        let levels = data.world.read_resource::<Rules>().breakout.levels.len();
 
-impl SimpleState for GameOver {
-    fn on_start(&mut self, data: StateData<GameData>) {
-        generic_message(
-            data.world,
-            self.font.clone(),
-            Anchor::Middle,
-            "Game Over",
-            None,
-        );
+        if lives == 0 {
+            Trans::Replace(Box::new(Intermission::new(font, "Game Over", None)))
+        } else if bricks > 0 {
+            trans
+        } else if self.level + 1 < levels {
+            let message = format!("Level {} Cleared", self.level + 1);
+            let next = Some((self.level + 1, lives));
+            Trans::Replace(Box::new(Intermission::new(font, &message, next)))
+        } else {
+            Trans::Replace(Box::new(Intermission::new(font, "You Win", None)))
+        }
     }
 
-    fn handle_event(&mut self, _: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+    fn handle_event(&mut self, data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
         match event {
-            StateEvent::Input(InputEvent::KeyPressed { .. }) => Trans::Quit,
-            _ => Trans::None,
+            // Restarting goes back to the start of this level, not of a match.
+            StateEvent::Input(InputEvent::ActionPressed(a)) if a == "pause" => {
+                let (font, level, lives) = (self.pong.font.clone(), self.level, self.lives);
+                let restart = move || {
+                    Trans::Replace(Box::new(Breakout::at_level(font.clone(), level, lives)))
+                };
+                Trans::Push(Box::new(Pause::new(
+                    self.pong.font.clone(),
+                    Box::new(restart),
+                )))
+            }
+            _ => SimpleState::handle_event(&mut self.pong, data, event),
         }
     }
 }
 
-/// The "paused" `State`.
-pub struct Pause {
-    text: Option<Entity>,
+/// A word between levels of breakout, or at the end of the game. Any key
+/// moves on to the `next` level, with the lives left, or back to the main
+/// menu if there isn't one.
+pub struct Intermission {
     font: FontHandle,
+    message: String,
+    next: Option<(usize, u32)>,
+    entities: Vec<Entity>,
 }
 
-impl Pause {
-    fn new(font: FontHandle) -> Pause {
-        Pause { text: None, font }
+impl Intermission {
+    fn new(font: FontHandle, message: &str, next: Option<(usize, u32)>) -> Intermission {
+        Intermission {
+            font,
+            message: message.to_string(),
+            next,
+            entities: vec![],
+        }
     }
 }
 
-impl SimpleState for Pause {
+impl SimpleState for Intermission {
     fn on_start(&mut self, data: StateData<GameData>) {
         let world = data.world;
-        let entity = initialize_pause_message(world, self.font.clone());
-        self.text.replace(entity);
-        world.read_resource::<AudioSink>().pause();
+        let message = generic_message(
+            world,
+            self.font.clone(),
+            Anchor::Middle,
+            &self.message,
+            None,
+        );
+        let prompt = generic_message(
+            world,
+            self.font.clone(),
+            Anchor::BottomMiddle,
+            "Press any key",
+            Some(25.0),
+        );
+        self.entities = vec![message, prompt];
+    }
+
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        let _ = data.world.delete_entities(&self.entities);
     }
 
     fn handle_event(&mut self, _: StateData<GameData>, event: StateEvent) -> SimpleTrans {
         match event {
-            StateEvent::Input(InputEvent::ActionPressed(a)) if a == "quit" => Trans::Quit,
-            StateEvent::Input(InputEvent::ActionPressed(a)) if a == "pause" => Trans::Pop,
+            StateEvent::Input(InputEvent::KeyPressed { .. }) => match self.next {
+                Some((level, lives)) => Trans::Replace(Box::new(Breakout::at_level(
+                    self.font.clone(),
+                    level,
+                    Some(lives),
+                ))),
+                None => Trans::Replace(Box::new(MainMenu::new(self.font.clone()))),
+            },
             _ => Trans::None,
         }
     }
-
-    fn on_stop(&mut self, data: StateData<GameData>) {
-        data.world.read_resource::<AudioSink>().play();
-        self.text.take().iter_mut().for_each(|entity| {
-            let _ = data.world.delete_entity(*entity);
-        });
-    }
 }
 
-/// The main game `State`.
-pub struct Pong<'a, 'b> {
-    sprite_sheet: Option<Handle<SpriteSheet>>,
-    dispatcher: Option<Dispatcher<'a, 'b>>,
-    font: FontHandle,
-    entities: Vec<Entity>,
-    fps: Option<Entity>,
+/// A recorded match, played back in place of live input.
+pub struct Replay<'a, 'b> {
+    pong: Pong<'a, 'b>,
+    recording: replay::Replay,
+    /// The rules and randomness in force beforehand, to be put back once the
+    /// replay is over.
+    live_rules: Option<Rules>,
+    live_random: Option<Random>,
 }
 
-impl<'a, 'b> Pong<'a, 'b> {
-    pub fn new(font: FontHandle) -> Pong<'a, 'b> {
-        Pong {
-            sprite_sheet: None,
-            dispatcher: None,
-            font,
-            entities: vec![],
-            fps: None,
+impl<'a, 'b> Replay<'a, 'b> {
+    pub fn new(font: FontHandle, recording: replay::Replay) -> Replay<'a, 'b> {
+        Replay {
+            pong: Pong::new(font, recording.players),
+            recording,
+            live_rules: None,
+            live_random: None,
         }
     }
+
+    /// Whether the recording has run out without the match being won. A match
+    /// decided by the very last step is left for `Pong` to end.
+    fn stopped_short(&self, world: &World) -> bool {
+        let rules = world.read_resource::<Rules>();
+
+        world.read_resource::<Playback>().finished()
+            && world.read_resource::<ScoreBoard>().result(&rules).is_none()
+    }
 }
 
-impl<'a, 'b> SimpleState for Pong<'a, 'b> {
+impl<'a, 'b> SimpleState for Replay<'a, 'b> {
     fn on_start(&mut self, data: StateData<GameData>) {
-        let world = data.world;
-
-        // Initial the system dispatcher unique to the "running" game state.
-        let mut builder = DispatcherBuilder::new();
-        builder.add(systems::MoveBallSystem, "ball_system", &[]);
-        builder.add(systems::PaddleSystem, "paddle_system", &[]);
-        builder.add(
-            systems::BounceSystem,
-            "collision_system",
-            &["paddle_system", "ball_system"],
-        );
-        builder.add(systems::ScoreSystem, "score_system", &["ball_system"]);
-        builder.add(systems::FpsSystem, "fps_system", &[]);
+        // The match must be played under the rules and seed it was recorded with.
+        self.live_rules = data.world.remove::<Rules>();
+        self.live_random = data.world.remove::<Random>();
+        data.world.insert(self.recording.rules.clone());
+        data.world.insert(Random::new(self.recording.seed));
+        data.world.insert(Playback::new(&self.recording));
+        SimpleState::on_start(&mut self.pong, data);
+    }
 
-        let mut dispatcher = builder
-            .with_pool((*world.read_resource::<ArcThreadPool>()).clone())
-            .build();
-        dispatcher.setup(world);
-        self.dispatcher = Some(dispatcher);
+    fn on_stop(&mut self, data: StateData<GameData>) {
+        data.world.remove::<Playback>();
 
-        // Set up the sprites.
-        let sprite_sheet_handle = load_sprite_sheet(world);
-        self.sprite_sheet.replace(sprite_sheet_handle);
+        if let Some(rules) = self.live_rules.take() {
+            data.world.insert(rules);
+        }
 
-        // Create all entities.
-        let (left, right) = initialize_paddles(world, self.sprite_sheet.clone().unwrap());
-        initialize_scoreboard(world, self.font.clone());
-        let ball = initialize_ball(world, self.sprite_sheet.clone().unwrap());
-        let ready = initialize_ready_msg(world, self.font.clone());
-        let fps = initialize_fps(world, self.font.clone());
-        let entities = vec![left, right, ball, ready, fps];
-        self.entities = entities;
-        self.fps = Some(fps);
-    }
+        if let Some(random) = self.live_random.take() {
+            data.world.insert(random);
+        }
 
-    fn on_stop(&mut self, data: StateData<GameData>) {
-        // This state will never be used again, so we remove all of its entities.
-        let _ = data.world.delete_entities(&self.entities);
+        SimpleState::on_stop(&mut self.pong, data);
     }
 
     fn update(&mut self, data: &mut StateData<GameData>) -> SimpleTrans {
-        // Special scope to make the borrowed `score_board` disappear as soon as
-        // it's no longer needed. The dispatch below will invoke a system that
-        // wants to borrow the `ScoreBoard` too, which causes a panic.
-        {
-            let score_board = data.world.read_resource::<ScoreBoard>();
-
-            if score_board.score_left >= 10 || score_board.score_right >= 10 {
-                return Trans::Replace(Box::new(GameOver {
-                    font: self.font.clone(),
-                }));
+        // The match is given the chance to end as it did when recorded. A
+        // recording that stops short of a result goes back to the menu.
+        match SimpleState::update(&mut self.pong, data) {
+            Trans::None if self.stopped_short(data.world) => {
+                Trans::Replace(Box::new(MainMenu::new(self.pong.font.clone())))
             }
+            trans => trans,
         }
-
-        // Run all `Systems` unique to this `State`.
-        if let Some(dispatcher) = self.dispatcher.as_mut() {
-            dispatcher.dispatch(&data.world);
-        }
-
-        Trans::None
     }
 
-    fn handle_event(&mut self, _: StateData<GameData>, event: StateEvent) -> SimpleTrans {
+    fn handle_event(&mut self, data: StateData<GameData>, event: StateEvent) -> SimpleTrans {
         match event {
-            StateEvent::Input(InputEvent::ActionPressed(a)) if a == "quit" => Trans::Quit,
+            // Restarting plays the recording again from the beginning.
             StateEvent::Input(InputEvent::ActionPressed(a)) if a == "pause" => {
-                Trans::Push(Box::new(Pause::new(self.font.clone())))
+                let (font, recording) = (self.pong.font.clone(), self.recording.clone());
+                let restart =
+                    move || Trans::Replace(Box::new(Replay::new(font.clone(), recording.clone())));
+                Trans::Push(Box::new(Pause::new(
+                    self.pong.font.clone(),
+                    Box::new(restart),
+                )))
             }
-            // TODO Remove later.
-            StateEvent::Input(InputEvent::KeyTyped('z')) => Trans::Replace(Box::new(GameOver {
-                font: self.font.clone(),
-            })),
-            _ => Trans::None,
+            _ => SimpleState::handle_event(&mut self.pong, data, event),
         }
     }
 }
 
+/// Put everything that moves back where the last step left it, undoing the
+/// blending of the previous frame.
+fn restore_positions(world: &mut World) {
+    let interpolated = world.read_storage::<Interpolated>();
+    let mut transforms = world.write_storage::<Transform>();
+
+    for (interpolated, transform) in (&interpolated, &mut transforms).join() {
+        transform.set_translation_x(interpolated.current[0]);
+        transform.set_translation_y(interpolated.current[1]);
+    }
+}
+
+/// Note where everything that moves ended up after a step.
+fn track_positions(world: &mut World) {
+    let mut interpolated = world.write_storage::<Interpolated>();
+    let transforms = world.read_storage::<Transform>();
+
+    for (interpolated, transform) in (&mut interpolated, &transforms).join() {
+        interpolated.previous = interpolated.current;
+        interpolated.current = [transform.translation().x, transform.translation().y];
+    }
+}
+
+/// Draw everything that moves `alpha` of the way between the last two steps.
+fn blend_positions(world: &mut World, alpha: f32) {
+    let interpolated = world.read_storage::<Interpolated>();
+    let mut transforms = world.write_storage::<Transform>();
+
+    for (interpolated, transform) in (&interpolated, &mut transforms).join() {
+        let [x, y] = interpolated.blend(alpha);
+        transform.set_translation_x(x);
+        transform.set_translation_y(y);
+    }
+}
+
 fn initialize_camera(world: &mut World) {
     let mut transform = Transform::default();
     transform.set_translation_xyz(ARENA_WIDTH * 0.5, ARENA_HEIGHT * 0.5, 1.0);
@@ -237,62 +1608,232 @@ fn initialize_camera(world: &mut World) {
         .build();
 }
 
-fn initialize_paddles(world: &mut World, sprite_sheet: Handle<SpriteSheet>) -> (Entity, Entity) {
-    let mut left_transform = Transform::default();
-    let mut right_transform = Transform::default();
+/// Point the camera at an arena of the given size, filling the window with it.
+fn fit_camera(world: &mut World, width: f32, height: f32) {
+    let mut cameras = world.write_storage::<Camera>();
+    let mut transforms = world.write_storage::<Transform>();
 
-    let y = ARENA_HEIGHT / 2.0;
-    left_transform.set_translation_xyz(PADDLE_WIDTH * 0.5, y, 0.0);
-    right_transform.set_translation_xyz(ARENA_WIDTH - PADDLE_WIDTH * 0.5, y, 0.0);
+    for (camera, transform) in (&mut cameras, &mut transforms).join() {
+        *camera = Camera::standard_2d(width, height);
+        transform.set_translation_xyz(width * 0.5, height * 0.5, 1.0);
+    }
+}
 
+fn initialize_paddles(
+    world: &mut World,
+    sprite_sheet: Handle<SpriteSheet>,
+    players: Players,
+) -> Vec<Entity> {
     // A component to actually render the paddles.
     let sprite_render = SpriteRender {
         sprite_sheet,
-        sprite_number: 0,
+        sprite_number: PADDLE_SPRITE,
     };
+    let arena = (*world.read_resource::<Arena>()).clone();
+    let rules = (*world.read_resource::<Rules>()).clone();
 
-    let left = world
-        .create_entity()
-        .with(sprite_render.clone())
-        .with(Paddle::new(Side::Left))
-        .with(left_transform)
-        .build();
+    Side::ALL
+        .iter()
+        .filter_map(|side| players.controller(*side).map(|c| (*side, c)))
+        .map(|(side, controller)| {
+            let start = sim::paddle_start(side, &arena);
+            let mut transform = Transform::default();
+            transform.set_translation_xyz(start[0], start[1], 0.0);
 
-    let right = world
-        .create_entity()
-        .with(sprite_render)
-        .with(Paddle::new(Side::Right))
-        .with(right_transform)
-        .build();
+            // The sprite stands upright, so paddles on the top and bottom are
+            // turned on their side.
+            if side.horizontal() {
+                transform.set_rotation_2d(std::f32::consts::FRAC_PI_2);
+            }
 
-    (left, right)
+            world
+                .create_entity()
+                .with(sprite_render.clone())
+                .with(Paddle::new(side, controller, &rules))
+                .with(transform)
+                .with(Interpolated::at(start))
+                .build()
+        })
+        .collect()
 }
 
-fn initialize_ball(world: &mut World, sprite_sheet: Handle<SpriteSheet>) -> Entity {
-    let mut local_transform = Transform::default();
-    local_transform.set_translation_xyz(ARENA_WIDTH / 2.0, ARENA_HEIGHT / 2.0, 0.0);
+/// Create the corner blocks between guarded walls, the arena's obstacles, and
+/// the walls either side of its goals.
+fn initialize_obstacles(
+    world: &mut World,
+    sprite_sheet: Handle<SpriteSheet>,
+    players: Players,
+) -> Vec<Entity> {
+    let obstacles: Vec<Obstacle> = {
+        let arena = world.read_resource::<Arena>();
+        let sides = players.sides();
+        sim::corners(&arena, &sides)
+            .into_iter()
+            .chain(arena.obstacles.iter().copied())
+            .chain(goal_walls(&arena, &sides))
+            .collect()
+    };
+
+    obstacles
+        .into_iter()
+        .map(|obstacle| {
+            // Blocks are the paddle sprite and posts the ball sprite, stretched
+            // to size.
+            let (sprite_number, [x, y], scale) = match obstacle {
+                Obstacle::Rect { centre, size } => (
+                    PADDLE_SPRITE,
+                    centre,
+                    [size[0] / PADDLE_WIDTH, size[1] / PADDLE_HEIGHT],
+                ),
+                Obstacle::Circle { centre, radius } => {
+                    let scale = radius / BALL_RADIUS;
+                    (BALL_SPRITE, centre, [scale, scale])
+                }
+            };
+            let mut transform = Transform::default();
+            transform.set_translation_xyz(x, y, 0.0);
+            transform.set_scale(Vector3::new(scale[0], scale[1], 1.0));
+
+            world
+                .create_entity()
+                .with(SpriteRender {
+                    sprite_sheet: sprite_sheet.clone(),
+                    sprite_number,
+                })
+                .with(transform)
+                .build()
+        })
+        .collect()
+}
+
+/// The stretches of each guarded wall that lie outside its goals, as thin
+/// blocks so that they can be drawn. Balls bounce off them all the same.
+fn goal_walls(arena: &Arena, sides: &[Side]) -> Vec<Obstacle> {
+    const THICKNESS: f32 = 1.0;
+    let mut walls = Vec::new();
+
+    for side in sides.iter() {
+        let mut goals: Vec<[f32; 2]> = arena
+            .goals
+            .iter()
+            .filter(|goal| goal.side == *side)
+            .map(|goal| [goal.from, goal.to])
+            .collect();
+
+        // A wall without goals is open all the way along.
+        if goals.is_empty() {
+            continue;
+        }
+
+        goals.sort_by(|a, b| a[0].total_cmp(&b[0]));
+
+        let length = if side.horizontal() {
+            arena.width
+        } else {
+            arena.height
+        };
+        let across = match side {
+            Side::Left | Side::Bottom => THICKNESS * 0.5,
+            Side::Right => arena.width - THICKNESS * 0.5,
+            Side::Top => arena.height - THICKNESS * 0.5,
+        };
+        let mut stretches = Vec::new();
+        let mut from = 0.0;
+
+        for [start, end] in goals {
+            if start > from {
+                stretches.push([from, start]);
+            }
+            from = f32::max(from, end);
+        }
+
+        if from < length {
+            stretches.push([from, length]);
+        }
 
+        walls.extend(stretches.into_iter().map(|[from, to]| {
+            let along = (from + to) * 0.5;
+
+            if side.horizontal() {
+                Obstacle::Rect {
+                    centre: [along, across],
+                    size: [to - from, THICKNESS],
+                }
+            } else {
+                Obstacle::Rect {
+                    centre: [across, along],
+                    size: [THICKNESS, to - from],
+                }
+            }
+        }));
+    }
+
+    walls
+}
+
+/// Create the bricks of a breakout level.
+fn initialize_bricks(
+    world: &mut World,
+    sprite_sheet: Handle<SpriteSheet>,
+    bricks: Vec<(Brick, [f32; 2])>,
+) {
+    // The paddle sprite, stretched to size.
     let sprite_render = SpriteRender {
         sprite_sheet,
-        sprite_number: 1,
+        sprite_number: PADDLE_SPRITE,
     };
 
-    let ball = Ball {
-        radius: BALL_RADIUS,
-        velocity: [BALL_VELOCITY_X, BALL_VELOCITY_Y],
+    for (brick, [x, y]) in bricks {
+        let mut transform = Transform::default();
+        transform.set_translation_xyz(x, y, 0.0);
+        transform.set_scale(Vector3::new(
+            brick.size[0] / PADDLE_WIDTH,
+            brick.size[1] / PADDLE_HEIGHT,
+            1.0,
+        ));
+
+        world
+            .create_entity()
+            .with(sprite_render.clone())
+            .with(brick)
+            .with(transform)
+            .build();
+    }
+}
+
+/// Create the balls a match starts with.
+fn initialize_balls(world: &mut World, sprite_sheet: Handle<SpriteSheet>, players: Players) {
+    let sprite_render = SpriteRender {
+        sprite_sheet: sprite_sheet.clone(),
+        sprite_number: BALL_SPRITE,
     };
 
-    let active = Active {
-        countdown: Some(1.0),
+    let balls: Vec<sim::SimBall> = {
+        let rules = world.read_resource::<Rules>();
+        let arena = world.read_resource::<Arena>();
+        let mut random = world.write_resource::<Random>();
+        let sides = players.sides();
+        (0..rules.balls)
+            .map(|i| sim::serve_ball(&rules, &arena, i, &sides, &mut random))
+            .collect()
     };
 
-    world
-        .create_entity()
-        .with(sprite_render)
-        .with(ball)
-        .with(local_transform)
-        .with(active)
-        .build()
+    for serve in balls {
+        let mut local_transform = Transform::default();
+        let [x, y] = serve.position;
+        local_transform.set_translation_xyz(x, y, 0.0);
+
+        world
+            .create_entity()
+            .with(sprite_render.clone())
+            .with(serve.ball)
+            .with(local_transform)
+            .with(serve.active)
+            .with(Interpolated::at(serve.position))
+            .build();
+    }
+
+    world.insert(Sprites(sprite_sheet));
 }
 
 fn generic_message(
@@ -319,10 +1860,76 @@ fn generic_message(
     world
         .create_entity()
         .with(transform)
-        .with(UiText::new(font, m2, [1.0, 1.0, 1.0, 1.0], text_size))
+        .with(UiText::new(
+            font,
+            m2,
+            [1.0, 1.0, 1.0, 1.0],
+            text_size,
+            LineMode::Single,
+            Anchor::Middle,
+        ))
         .build()
 }
 
+/// A column of lines down the middle of the screen, one entity for each, the
+/// first of them `top` above the centre.
+fn initialize_menu(world: &mut World, font: FontHandle, top: f32, lines: &[&str]) -> Vec<Entity> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let text_size = 25.0;
+            let transform = UiTransform::new(
+                line.to_string(),
+                Anchor::Middle,
+                Anchor::Middle,
+                0.0,
+                top - i as f32 * 28.0,
+                1.0,
+                text_size * line.chars().count() as f32,
+                text_size,
+            );
+
+            world
+                .create_entity()
+                .with(transform)
+                .with(UiText::new(
+                    font.clone(),
+                    line.to_string(),
+                    [1.0, 1.0, 1.0, 1.0],
+                    text_size,
+                    LineMode::Single,
+                    Anchor::Middle,
+                ))
+                .build()
+        })
+        .collect()
+}
+
+/// Move the selection from the `selected` menu entry by `by` entries,
+/// wrapping round at either end. Yields the newly selected entry.
+fn select(world: &mut World, entries: &[Entity], selected: usize, by: isize) -> usize {
+    let count = entries.len() as isize;
+    let selected = (selected as isize + by).rem_euclid(count) as usize;
+    highlight(world, entries, selected);
+    selected
+}
+
+/// Light up the `selected` menu entry, and dim the rest.
+fn highlight(world: &mut World, entries: &[Entity], selected: usize) {
+    let mut texts = world.write_storage::<UiText>();
+
+    for (i, entry) in entries.iter().enumerate() {
+        if let Some(text) = texts.get_mut(*entry) {
+            text.color = if i == selected {
+                [1.0, 1.0, 1.0, 1.0]
+            } else {
+                [0.4, 0.4, 0.4, 1.0]
+            };
+        }
+    }
+}
+
 fn initialize_pause_message(world: &mut World, font: FontHandle) -> Entity {
     generic_message(world, font, Anchor::Middle, "Paused", None)
 }
@@ -333,6 +1940,35 @@ fn initialize_ready_msg(world: &mut World, font: FontHandle) -> Entity {
     text
 }
 
+fn initialize_lives(world: &mut World, font: FontHandle, lives: u32) -> Entity {
+    let transform = UiTransform::new(
+        "lives".to_string(),
+        Anchor::BottomLeft,
+        Anchor::BottomLeft,
+        20.0,
+        20.0,
+        1.0,
+        300.0,
+        50.0,
+    );
+
+    let text = world
+        .create_entity()
+        .with(transform)
+        .with(UiText::new(
+            font,
+            format!("Lives {}", lives),
+            [1.0, 1.0, 1.0, 1.0],
+            35.0,
+            LineMode::Single,
+            Anchor::MiddleLeft,
+        ))
+        .build();
+
+    world.insert(LivesText(text));
+    text
+}
+
 fn initialize_fps(world: &mut World, font: FontHandle) -> Entity {
     let msg = {
         let fps = world.read_resource::<FpsCounter>();
@@ -343,53 +1979,92 @@ fn initialize_fps(world: &mut World, font: FontHandle) -> Entity {
     text
 }
 
-fn initialize_scoreboard(world: &mut World, font: FontHandle) {
-    let p1_transform = UiTransform::new(
-        "P1".to_string(),
-        Anchor::TopMiddle,
-        Anchor::TopMiddle,
-        -50.0,
-        -50.0,
-        1.0,
-        200.0,
-        50.0,
-    );
+/// Create the scores of every side in play, or the rally counters when
+/// practicing alone.
+fn initialize_scoreboard(world: &mut World, font: FontHandle, players: Players) -> Vec<Entity> {
+    if players.solo() {
+        let mut counter = |id: &str, x: f32| {
+            let transform = UiTransform::new(
+                id.to_string(),
+                Anchor::TopMiddle,
+                Anchor::TopMiddle,
+                x,
+                -50.0,
+                1.0,
+                300.0,
+                50.0,
+            );
 
-    let p2_transform = UiTransform::new(
-        "P2".to_string(),
-        Anchor::TopMiddle,
-        Anchor::TopMiddle,
-        50.0,
-        -50.0,
-        1.0,
-        200.0,
-        50.0,
-    );
+            world
+                .create_entity()
+                .with(transform)
+                .with(UiText::new(
+                    font.clone(),
+                    String::new(),
+                    [1.0, 1.0, 1.0, 1.0],
+                    35.0,
+                    LineMode::Single,
+                    Anchor::Middle,
+                ))
+                .build()
+        };
+        let current = counter("rally", -120.0);
+        let best = counter("best", 120.0);
+        world.insert(RallyText { current, best });
+        world.insert(ScoreText(Vec::new()));
 
-    let p1_score = world
-        .create_entity()
-        .with(p1_transform)
-        .with(UiText::new(
-            font.clone(),
-            "0".to_string(),
-            [1.0, 1.0, 1.0, 1.0],
-            50.0,
-        ))
-        .build();
+        return vec![current, best];
+    }
 
-    let p2_score = world
-        .create_entity()
-        .with(p2_transform)
-        .with(UiText::new(
-            font,
-            "0".to_string(),
-            [1.0, 1.0, 1.0, 1.0],
-            50.0,
-        ))
-        .build();
+    let texts: Vec<(Side, Entity)> = players
+        .sides()
+        .into_iter()
+        .map(|side| {
+            let (anchor, x, y) = match side {
+                Side::Left => (Anchor::TopMiddle, -50.0, -50.0),
+                Side::Right => (Anchor::TopMiddle, 50.0, -50.0),
+                Side::Top => (Anchor::TopMiddle, 0.0, -110.0),
+                Side::Bottom => (Anchor::BottomMiddle, 0.0, 60.0),
+            };
+            let transform = UiTransform::new(
+                side.player().to_string(),
+                anchor,
+                anchor,
+                x,
+                y,
+                1.0,
+                200.0,
+                50.0,
+            );
+
+            let text = {
+                let score_board = world.read_resource::<ScoreBoard>();
+                let rules = world.read_resource::<Rules>();
+                score_board.display(side, &rules)
+            };
+
+            let entity = world
+                .create_entity()
+                .with(transform)
+                .with(UiText::new(
+                    font.clone(),
+                    text,
+                    [1.0, 1.0, 1.0, 1.0],
+                    50.0,
+                    LineMode::Single,
+                    Anchor::Middle,
+                ))
+                .build();
+
+            (side, entity)
+        })
+        .collect();
+
+    let entities = texts.iter().map(|(_, entity)| *entity).collect();
 
     // TODO Why is this insert necessary?
-    world.insert(ScoreText { p1_score, p2_score });
+    world.insert(ScoreText(texts));
+    entities
 }
 
 fn initialize_logo(world: &mut World) -> Entity {
@@ -419,7 +2094,8 @@ fn initialize_logo(world: &mut World) -> Entity {
     };
 
     let mut local_transform = Transform::default();
-    local_transform.set_translation_xyz(ARENA_WIDTH / 2.0, ARENA_HEIGHT / 2.0, 0.0);
+    // Above the menu, which takes up the lower half of the screen.
+    local_transform.set_translation_xyz(ARENA_WIDTH * 0.5, ARENA_HEIGHT * 0.75, 0.0);
 
     world
         .create_entity()
@@ -428,6 +2104,18 @@ fn initialize_logo(world: &mut World) -> Entity {
         .build()
 }
 
+/// Start loading the court named in the `Rules`, from `assets/arenas/`.
+fn load_arena(world: &mut World, progress: &mut ProgressCounter) -> Handle<Arena> {
+    let name = format!("arenas/{}.ron", world.read_resource::<Rules>().arena);
+
+    world.read_resource::<Loader>().load(
+        name,
+        RonFormat,
+        progress,
+        &world.read_resource::<AssetStorage<Arena>>(),
+    )
+}
+
 fn load_sprite_sheet(world: &mut World) -> Handle<SpriteSheet> {
     let loader = world.read_resource::<Loader>();
     let texture_storage = world.read_resource::<AssetStorage<Texture>>();
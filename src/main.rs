@@ -1,4 +1,6 @@
+use amethyst::assets::Processor;
 use amethyst::audio::{AudioBundle, DjSystemDesc};
+use amethyst::config::Config;
 use amethyst::core::transform::TransformBundle;
 use amethyst::input::{InputBundle, StringBindings};
 use amethyst::prelude::*;
@@ -7,13 +9,35 @@ use amethyst::renderer::types::DefaultBackend;
 use amethyst::renderer::RenderingBundle;
 use amethyst::ui::{RenderUi, UiBundle};
 use amethyst::utils::fps_counter::FpsCounterBundle;
+use pong::arena::Arena;
 use pong::audio::Music;
+use pong::controls::UserBindings;
+use pong::core::Random;
+use pong::level::Level;
+use pong::replay::ReplayDir;
+use pong::rules::Rules;
 
 fn main() -> amethyst::Result<()> {
     amethyst::start_logger(Default::default());
     let app_root = amethyst::utils::application_root_dir()?;
     let display_config_path = app_root.join("config").join("display.ron");
-    let binding_path = app_root.join("config").join("bindings.ron");
+    let user_bindings = UserBindings(app_root.join("config").join("user_bindings.ron"));
+    // Bindings changed and saved in the game take the place of the defaults.
+    let binding_path = if user_bindings.path().exists() {
+        user_bindings.path().to_path_buf()
+    } else {
+        app_root.join("config").join("bindings.ron")
+    };
+    let rules_path = app_root.join("config").join("rules.ron");
+
+    let rules = Rules::load(rules_path)?;
+    rules.validate()?;
+
+    let seed = match seed_arg()? {
+        Some(seed) => seed,
+        None => rand::random(),
+    };
+    log::info!("Seed: {}", seed);
 
     let rendering_bundle = RenderingBundle::<DefaultBackend>::new()
         .with_plugin(
@@ -31,7 +55,9 @@ fn main() -> amethyst::Result<()> {
         .with_bundle(input_bundle)?
         .with_bundle(UiBundle::<StringBindings>::new())?
         .with_bundle(AudioBundle::default())?
-        .with_bundle(FpsCounterBundle::default())?
+        .with_bundle(FpsCounterBundle)?
+        .with(Processor::<Arena>::new(), "arena_processor", &[])
+        .with(Processor::<Level>::new(), "level_processor", &[])
         .with_system_desc(
             DjSystemDesc::new(|music: &mut Music| music.music.next()),
             "dj_system",
@@ -39,8 +65,26 @@ fn main() -> amethyst::Result<()> {
         );
 
     let assets_dir = app_root.join("assets");
-    let mut game = Application::new(assets_dir, pong::Welcome::default(), game_data)?;
+    let mut game = Application::build(assets_dir, pong::Welcome::default())?
+        .with_resource(rules)
+        .with_resource(Random::new(seed))
+        .with_resource(ReplayDir(app_root.join("replays")))
+        .with_resource(user_bindings)
+        .build(game_data)?;
     game.run();
 
     Ok(())
 }
+
+/// The seed given by `--seed <number>`, if any. The same seed and inputs always
+/// play out the same match.
+fn seed_arg() -> amethyst::Result<Option<u64>> {
+    let mut args = std::env::args().skip_while(|arg| arg != "--seed").skip(1);
+
+    match args.next() {
+        None => Ok(None),
+        Some(seed) => seed.parse().map(Some).map_err(|_| {
+            amethyst::Error::from_string(format!("Invalid seed `{}`: must be a number", seed))
+        }),
+    }
+}
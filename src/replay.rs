@@ -0,0 +1,184 @@
+//! Recording matches, and playing them back exactly.
+
+use crate::core::Players;
+use crate::rules::Rules;
+use crate::sim::Inputs;
+use amethyst::config::{Config, ConfigError};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// The file in the `ReplayDir` that the most recent match is saved to.
+pub const LAST_REPLAY: &str = "last.ron";
+
+/// Everything needed to play a match back: the rules, players and seed it
+/// began with, and the inputs of every step. Inputs tend to stay the same for
+/// many steps at a time, so only the changes are kept.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub rules: Rules,
+    pub players: Players,
+    pub seed: u64,
+    /// How many steps were played.
+    pub length: usize,
+    /// The step at which the inputs changed, and what they changed to.
+    pub changes: Vec<(usize, Inputs)>,
+}
+
+impl Replay {
+    pub fn new(rules: Rules, players: Players, seed: u64) -> Replay {
+        Replay {
+            rules,
+            players,
+            seed,
+            length: 0,
+            changes: Vec::new(),
+        }
+    }
+
+    /// Add the inputs of the next step to the end of the recording.
+    pub fn record(&mut self, inputs: Inputs) {
+        let last = self.changes.last().map(|(_, inputs)| *inputs);
+
+        if last.unwrap_or_default() != inputs {
+            self.changes.push((self.length, inputs));
+        }
+
+        self.length += 1;
+    }
+
+    /// Save the replay, creating its directory if need be.
+    pub fn save(&self, path: &Path) -> Result<(), ConfigError> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        self.write(path)
+    }
+}
+
+/// Where replays are kept.
+pub struct ReplayDir(pub PathBuf);
+
+impl ReplayDir {
+    pub fn last(&self) -> PathBuf {
+        self.0.join(LAST_REPLAY)
+    }
+}
+
+/// The match in progress, as it's being recorded.
+pub struct Recorder(pub Replay);
+
+/// A replay being fed back into the game in place of live input.
+pub struct Playback {
+    pub length: usize,
+    pub changes: Vec<(usize, Inputs)>,
+    /// The step to be played next.
+    pub next: usize,
+    /// The inputs in force, until the next change comes due.
+    current: Inputs,
+    /// The change to be applied next.
+    change: usize,
+}
+
+impl Playback {
+    pub fn new(replay: &Replay) -> Playback {
+        Playback {
+            length: replay.length,
+            changes: replay.changes.clone(),
+            next: 0,
+            current: Inputs::default(),
+            change: 0,
+        }
+    }
+
+    /// Whether every recorded step has been played.
+    pub fn finished(&self) -> bool {
+        self.next >= self.length
+    }
+
+    /// Move on to the next step, yielding the inputs of this one.
+    pub fn advance(&mut self) -> Inputs {
+        while let Some((step, inputs)) = self.changes.get(self.change) {
+            if *step > self.next {
+                break;
+            }
+
+            self.current = *inputs;
+            self.change += 1;
+        }
+
+        self.next += 1;
+
+        if self.next > self.length {
+            Inputs::default()
+        } else {
+            self.current
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arena::Arena;
+    use crate::core::Controller;
+    use crate::sim::PongSim;
+
+    /// Some made-up play for the left paddle: up, down and still in turn, with
+    /// a serve now and then.
+    fn inputs(step: usize) -> Inputs {
+        let axis = [1.0, 0.0, -1.0][step / 50 % 3];
+
+        Inputs {
+            left: Some(axis),
+            serve: [step.is_multiple_of(200), false, false, false],
+            ..Inputs::default()
+        }
+    }
+
+    #[test]
+    fn changes_expand_to_every_step() {
+        let mut replay = Replay::new(Rules::default(), Players::default(), 0);
+        let steps: Vec<Inputs> = (0..1000).map(inputs).collect();
+        steps.iter().for_each(|step| replay.record(*step));
+        assert!(replay.changes.len() < steps.len() / 10);
+
+        let mut playback = Playback::new(&replay);
+        let played: Vec<Inputs> = (0..steps.len()).map(|_| playback.advance()).collect();
+        assert_eq!(played, steps);
+        assert!(playback.finished());
+    }
+
+    #[test]
+    fn replays_play_out_the_same() {
+        let rules = Rules {
+            manual_serve: true,
+            ..Rules::default()
+        };
+        let players = Players {
+            right: Some(Controller::Computer),
+            ..Players::default()
+        };
+        let dt = 1.0 / rules.steps_per_second as f32;
+        let mut live = PongSim::new(rules.clone(), Arena::default(), players, 42);
+        let mut replay = Replay::new(rules.clone(), players, 42);
+
+        for step in 0..20_000 {
+            replay.record(inputs(step));
+            live.step(dt, &inputs(step));
+        }
+
+        let mut played = PongSim::new(replay.rules.clone(), Arena::default(), players, replay.seed);
+        let mut playback = Playback::new(&replay);
+
+        while !playback.finished() {
+            played.step(dt, &playback.advance());
+        }
+
+        assert!(live.winner().is_some());
+        assert_eq!(
+            format!("{:?}", played.score_board),
+            format!("{:?}", live.score_board)
+        );
+    }
+}
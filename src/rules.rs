@@ -0,0 +1,623 @@
+//! Tunable parameters of a match, loaded from `config/rules.ron`.
+
+use crate::core::Side;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The rules every match is played by. Any field missing from the file falls
+/// back to its default.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Rules {
+    /// The points needed to win a game.
+    pub points_per_game: u32,
+    /// Whether a game must be won by two clear points, playing on from deuce.
+    pub win_by_two: bool,
+    /// The games needed to win a set.
+    pub games_per_set: u32,
+    /// The sets needed to win the match.
+    pub sets_to_win: u32,
+    /// The angle, in degrees above or below the horizontal, that balls are
+    /// served at, on average.
+    pub serve_angle: f32,
+    /// How far, in degrees, a serve may stray either side of `serve_angle`.
+    pub serve_spread: f32,
+    /// Who serves after each point.
+    pub server: Server,
+    /// Whether human players launch their own serves with the `serve` action,
+    /// rather than waiting out the countdown.
+    pub manual_serve: bool,
+    /// How fast a ball travels over the course of a rally.
+    pub ball_speed: SpeedCurve,
+    /// How many balls are in play at the start of the match.
+    pub balls: u32,
+    /// Extra balls that join during long rallies, if any.
+    pub extra_balls: Option<ExtraBalls>,
+    /// Pickups that appear in the arena, if any.
+    pub power_ups: Option<PowerUps>,
+    /// The steepest angle, in degrees, that a ball can leave a paddle at.
+    /// Reached when it strikes the very edge.
+    pub max_deflection: f32,
+    /// How fast each side's paddle can go, how it gets up to speed and how
+    /// it comes to a stop.
+    pub paddle_motion: PaddleMotions,
+    /// The fraction of a paddle's velocity along its wall that it passes on
+    /// to a ball it hits.
+    pub momentum: f32,
+    /// Curve put on balls hit by a moving paddle, if any.
+    pub spin: Option<Spin>,
+    /// Seconds a ball waits before it is served.
+    pub serve_countdown: f32,
+    /// How hard the computer pushes its paddle, as a fraction of a full input axis.
+    pub computer_speed: f32,
+    /// How many times a second the game is advanced, whatever the frame rate.
+    pub steps_per_second: u32,
+    /// The court to play on, named after its file in `assets/arenas/`.
+    pub arena: String,
+    /// Head starts and hindrances, to even out a match between players of
+    /// different skill. They can be changed before each match.
+    pub handicaps: Handicaps,
+    /// How the brick-breaking game is played.
+    pub breakout: Breakout,
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules {
+            points_per_game: 10,
+            win_by_two: false,
+            games_per_set: 1,
+            sets_to_win: 1,
+            serve_angle: 37.5,
+            serve_spread: 10.0,
+            server: Server::Loser,
+            manual_serve: false,
+            ball_speed: SpeedCurve::default(),
+            balls: 1,
+            extra_balls: None,
+            power_ups: None,
+            max_deflection: 60.0,
+            paddle_motion: PaddleMotions::default(),
+            momentum: 0.25,
+            spin: None,
+            serve_countdown: 1.0,
+            computer_speed: 0.75,
+            steps_per_second: 120,
+            arena: "classic".to_string(),
+            handicaps: Handicaps::default(),
+            breakout: Breakout::default(),
+        }
+    }
+}
+
+impl Rules {
+    /// Check that every field holds a playable value.
+    pub fn validate(&self) -> Result<(), RulesError> {
+        check(
+            "points_per_game",
+            self.points_per_game > 0,
+            "must be at least 1",
+        )?;
+        check(
+            "games_per_set",
+            self.games_per_set > 0,
+            "must be at least 1",
+        )?;
+        check("sets_to_win", self.sets_to_win > 0, "must be at least 1")?;
+        check(
+            "serve_angle",
+            (0.0..90.0).contains(&self.serve_angle),
+            "must be at least 0.0 and less than 90.0",
+        )?;
+        check(
+            "serve_spread",
+            self.serve_spread >= 0.0 && self.serve_angle + self.serve_spread < 90.0,
+            "must not be negative, and must keep serves below 90.0",
+        )?;
+        if let Server::Alternate(every) = self.server {
+            check("server", every > 0, "must alternate every 1 point or more")?;
+        }
+        self.ball_speed.validate()?;
+        check("balls", self.balls > 0, "must be at least 1")?;
+        if let Some(extra) = self.extra_balls {
+            extra.validate(self.balls)?;
+        }
+        if let Some(power_ups) = self.power_ups {
+            power_ups.validate()?;
+        }
+        check(
+            "max_deflection",
+            (0.0..90.0).contains(&self.max_deflection),
+            "must be at least 0.0 and less than 90.0",
+        )?;
+        self.paddle_motion.validate()?;
+        check(
+            "momentum",
+            (0.0..=1.0).contains(&self.momentum),
+            "must be between 0.0 and 1.0",
+        )?;
+        if let Some(spin) = self.spin {
+            spin.validate()?;
+        }
+        check(
+            "serve_countdown",
+            self.serve_countdown.is_finite() && self.serve_countdown >= 0.0,
+            "must not be negative",
+        )?;
+        check(
+            "computer_speed",
+            (0.0..=1.0).contains(&self.computer_speed),
+            "must be between 0.0 and 1.0",
+        )?;
+        check(
+            "steps_per_second",
+            self.steps_per_second > 0,
+            "must be at least 1",
+        )?;
+        check("arena", !self.arena.is_empty(), "must name an arena")?;
+        self.handicaps.validate(self.points_per_game)?;
+        self.breakout.validate()
+    }
+}
+
+/// Who serves after a point. Serves are made from the server's paddle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Server {
+    /// The side that lost the point.
+    Loser,
+    /// The side that won the point.
+    Winner,
+    /// Each side in turn, changing over every so many points.
+    Alternate(u32),
+}
+
+/// The `PaddleMotion` of the paddle on each side. A side without one of its
+/// own moves by the `default`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PaddleMotions {
+    pub default: PaddleMotion,
+    pub left: Option<PaddleMotion>,
+    pub right: Option<PaddleMotion>,
+    pub top: Option<PaddleMotion>,
+    pub bottom: Option<PaddleMotion>,
+}
+
+impl Default for PaddleMotions {
+    fn default() -> PaddleMotions {
+        PaddleMotions {
+            default: PaddleMotion::Inertia {
+                max_speed: 72.0,
+                acceleration: 600.0,
+                friction: 400.0,
+            },
+            left: None,
+            right: None,
+            top: None,
+            bottom: None,
+        }
+    }
+}
+
+impl PaddleMotions {
+    fn validate(&self) -> Result<(), RulesError> {
+        self.default.validate("paddle_motion.default")?;
+
+        for side in Side::ALL.iter() {
+            if let Some(motion) = self.motion(*side) {
+                motion.validate(&format!("paddle_motion.{}", side_field(*side)))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The side's own motion, if it has one.
+    fn motion(&self, side: Side) -> Option<PaddleMotion> {
+        match side {
+            Side::Left => self.left,
+            Side::Right => self.right,
+            Side::Top => self.top,
+            Side::Bottom => self.bottom,
+        }
+    }
+
+    pub fn for_side(&self, side: Side) -> PaddleMotion {
+        self.motion(side).unwrap_or(self.default)
+    }
+}
+
+/// How a paddle's velocity follows its input axis. Either way, it never moves
+/// faster than `max_speed` units per second, scaled by its side's handicap.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum PaddleMotion {
+    /// Paddles move at the speed their input asks for straight away, and
+    /// stop dead once it's let go.
+    Instant { max_speed: f32 },
+    /// Paddles gain `acceleration` units per second, every second, toward the
+    /// speed their input asks for. Once it's let go, `friction` slows them
+    /// the same way until they come to rest.
+    Inertia {
+        max_speed: f32,
+        acceleration: f32,
+        friction: f32,
+    },
+}
+
+impl PaddleMotion {
+    /// How far the paddle moves per second at full input.
+    pub fn max_speed(&self) -> f32 {
+        match *self {
+            PaddleMotion::Instant { max_speed } => max_speed,
+            PaddleMotion::Inertia { max_speed, .. } => max_speed,
+        }
+    }
+
+    /// Check the motion given as `field`.
+    fn validate(&self, field: &str) -> Result<(), RulesError> {
+        let max_speed = self.max_speed();
+        check(
+            &format!("{}.max_speed", field),
+            max_speed.is_finite() && max_speed > 0.0,
+            "must be a positive number",
+        )?;
+
+        match *self {
+            PaddleMotion::Instant { .. } => Ok(()),
+            PaddleMotion::Inertia {
+                acceleration,
+                friction,
+                ..
+            } => {
+                check(
+                    &format!("{}.acceleration", field),
+                    acceleration.is_finite() && acceleration > 0.0,
+                    "must be a positive number",
+                )?;
+                check(
+                    &format!("{}.friction", field),
+                    friction.is_finite() && friction > 0.0,
+                    "must be a positive number",
+                )
+            }
+        }
+    }
+}
+
+/// How a ball's speed, in units per second, changes during a rally.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpeedCurve {
+    /// The speed of a freshly served ball.
+    pub base: f32,
+    /// Added each time the ball is hit by a paddle.
+    pub per_hit: f32,
+    /// Added every second the ball is in play.
+    pub per_second: f32,
+    /// The ball never goes faster than this.
+    pub max: f32,
+}
+
+impl Default for SpeedCurve {
+    fn default() -> SpeedCurve {
+        SpeedCurve {
+            base: 82.0,
+            per_hit: 4.0,
+            per_second: 1.0,
+            max: 200.0,
+        }
+    }
+}
+
+impl SpeedCurve {
+    fn validate(&self) -> Result<(), RulesError> {
+        check(
+            "ball_speed.base",
+            self.base.is_finite() && self.base > 0.0,
+            "must be a positive number",
+        )?;
+        check(
+            "ball_speed.per_hit",
+            self.per_hit.is_finite() && self.per_hit >= 0.0,
+            "must not be negative",
+        )?;
+        check(
+            "ball_speed.per_second",
+            self.per_second.is_finite() && self.per_second >= 0.0,
+            "must not be negative",
+        )?;
+        check(
+            "ball_speed.max",
+            self.max.is_finite() && self.max >= self.base,
+            "must be at least `ball_speed.base`",
+        )
+    }
+}
+
+/// During a rally, another ball joins every `every` seconds until there are
+/// `max` in play. Extra balls leave the arena once they score.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExtraBalls {
+    pub every: f32,
+    pub max: u32,
+}
+
+impl ExtraBalls {
+    fn validate(&self, balls: u32) -> Result<(), RulesError> {
+        check(
+            "extra_balls.every",
+            self.every.is_finite() && self.every > 0.0,
+            "must be a positive number",
+        )?;
+        check(
+            "extra_balls.max",
+            self.max > balls,
+            "must be greater than `balls`",
+        )
+    }
+}
+
+/// A power-up appears every `every` seconds, as long as there are fewer than
+/// `max` waiting. Their effects last `duration` seconds.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PowerUps {
+    pub every: f32,
+    pub duration: f32,
+    pub max: u32,
+}
+
+impl PowerUps {
+    fn validate(&self) -> Result<(), RulesError> {
+        check(
+            "power_ups.every",
+            self.every.is_finite() && self.every > 0.0,
+            "must be a positive number",
+        )?;
+        check(
+            "power_ups.duration",
+            self.duration.is_finite() && self.duration > 0.0,
+            "must be a positive number",
+        )?;
+        check("power_ups.max", self.max > 0, "must be at least 1")
+    }
+}
+
+/// A ball hit by a moving paddle curves back against the paddle's motion,
+/// turning `grip` degrees per second for every unit per second the paddle was
+/// moving. It loses `decay` of its spin every second.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Spin {
+    pub grip: f32,
+    pub decay: f32,
+}
+
+impl Spin {
+    fn validate(&self) -> Result<(), RulesError> {
+        check(
+            "spin.grip",
+            self.grip.is_finite() && self.grip >= 0.0,
+            "must not be negative",
+        )?;
+        check(
+            "spin.decay",
+            (0.0..=1.0).contains(&self.decay),
+            "must be between 0.0 and 1.0",
+        )
+    }
+}
+
+/// The `Handicap` of the player on each side.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Handicaps {
+    pub left: Handicap,
+    pub right: Handicap,
+    pub top: Handicap,
+    pub bottom: Handicap,
+}
+
+impl Handicaps {
+    pub fn for_side(&self, side: Side) -> Handicap {
+        match side {
+            Side::Left => self.left,
+            Side::Right => self.right,
+            Side::Top => self.top,
+            Side::Bottom => self.bottom,
+        }
+    }
+
+    pub fn for_side_mut(&mut self, side: Side) -> &mut Handicap {
+        match side {
+            Side::Left => &mut self.left,
+            Side::Right => &mut self.right,
+            Side::Top => &mut self.top,
+            Side::Bottom => &mut self.bottom,
+        }
+    }
+
+    /// The points each of the `sides` begins every game with, indexed by
+    /// `Side::index`.
+    pub fn head_start(&self, sides: &[Side]) -> [u32; 4] {
+        let mut points = [0; 4];
+
+        for side in sides {
+            points[side.index()] = self.for_side(*side).points;
+        }
+
+        points
+    }
+
+    fn validate(&self, points_per_game: u32) -> Result<(), RulesError> {
+        for side in Side::ALL.iter() {
+            self.for_side(*side)
+                .validate(&format!("handicaps.{}", side_field(*side)), points_per_game)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Adjustments to one player's paddle and score. A paddle's `paddle_length`
+/// and `paddle_speed` multiply the usual ones, and every game begins with
+/// `points` already won.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Handicap {
+    pub paddle_length: f32,
+    pub paddle_speed: f32,
+    pub points: u32,
+}
+
+impl Default for Handicap {
+    fn default() -> Handicap {
+        Handicap {
+            paddle_length: 1.0,
+            paddle_speed: 1.0,
+            points: 0,
+        }
+    }
+}
+
+impl Handicap {
+    /// Check the handicap given as `field`.
+    fn validate(&self, field: &str, points_per_game: u32) -> Result<(), RulesError> {
+        check(
+            &format!("{}.paddle_length", field),
+            self.paddle_length.is_finite() && self.paddle_length > 0.0,
+            "must be a positive number",
+        )?;
+        check(
+            &format!("{}.paddle_speed", field),
+            self.paddle_speed.is_finite() && self.paddle_speed > 0.0,
+            "must be a positive number",
+        )?;
+        check(
+            &format!("{}.points", field),
+            self.points < points_per_game,
+            "must be fewer than `points_per_game`",
+        )
+    }
+}
+
+/// The player has `lives` balls to lose while clearing each of the `levels`
+/// in turn, each named after its file in `assets/levels/`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Breakout {
+    pub lives: u32,
+    pub levels: Vec<String>,
+}
+
+impl Default for Breakout {
+    fn default() -> Breakout {
+        Breakout {
+            lives: 3,
+            levels: vec!["wall".to_string(), "checkers".to_string()],
+        }
+    }
+}
+
+impl Breakout {
+    fn validate(&self) -> Result<(), RulesError> {
+        check("breakout.lives", self.lives > 0, "must be at least 1")?;
+        check(
+            "breakout.levels",
+            !self.levels.is_empty() && self.levels.iter().all(|level| !level.is_empty()),
+            "must name at least one level",
+        )
+    }
+}
+
+/// The name of `side`'s field wherever rules are given side by side.
+fn side_field(side: Side) -> &'static str {
+    match side {
+        Side::Left => "left",
+        Side::Right => "right",
+        Side::Top => "top",
+        Side::Bottom => "bottom",
+    }
+}
+
+fn check(field: &str, ok: bool, reason: &'static str) -> Result<(), RulesError> {
+    if ok {
+        Ok(())
+    } else {
+        Err(RulesError {
+            field: field.to_string(),
+            reason,
+        })
+    }
+}
+
+/// A field of `Rules` held a value the game can't be played with.
+#[derive(Debug)]
+pub struct RulesError {
+    pub field: String,
+    pub reason: &'static str,
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid rule `{}`: {}", self.field, self.reason)
+    }
+}
+
+impl std::error::Error for RulesError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use amethyst::config::Config;
+
+    fn failing_field(rules: &Rules) -> String {
+        rules.validate().unwrap_err().field
+    }
+
+    #[test]
+    fn defaults_are_valid() {
+        assert!(Rules::default().validate().is_ok());
+        assert!(Rules::load("config/rules.ron").unwrap().validate().is_ok());
+    }
+
+    #[test]
+    fn errors_name_the_bad_field() {
+        let rules = Rules {
+            points_per_game: 0,
+            ..Rules::default()
+        };
+        assert_eq!(failing_field(&rules), "points_per_game");
+
+        let mut rules = Rules::default();
+        rules.ball_speed.max = 1.0;
+        assert_eq!(failing_field(&rules), "ball_speed.max");
+
+        let mut rules = Rules::default();
+        rules.handicaps.right.paddle_length = 0.0;
+        assert_eq!(failing_field(&rules), "handicaps.right.paddle_length");
+
+        let mut rules = Rules::default();
+        rules.handicaps.bottom.points = rules.points_per_game;
+        assert_eq!(failing_field(&rules), "handicaps.bottom.points");
+
+        let mut rules = Rules::default();
+        rules.paddle_motion.top = Some(PaddleMotion::Inertia {
+            max_speed: 72.0,
+            acceleration: 1.0,
+            friction: -1.0,
+        });
+        assert_eq!(failing_field(&rules), "paddle_motion.top.friction");
+    }
+
+    #[test]
+    fn misspelled_fields_are_refused() {
+        let error = Rules::load_bytes(b"(points_per_gam: 3)").unwrap_err();
+        assert!(error.to_string().contains("points_per_gam"));
+
+        let error =
+            Rules::load_bytes(b"(handicaps: (left: (points: 1, lenght: 2.0)))").unwrap_err();
+        assert!(error.to_string().contains("lenght"));
+    }
+}
@@ -0,0 +1,1334 @@
+//! The rules of the game, free of any rendering, audio or ECS concerns.
+//!
+//! The `Systems` in `crate::systems` delegate to the functions here, and
+//! `PongSim` strings them together into a match that can be run headless.
+
+use crate::arena::{Arena, Obstacle};
+use crate::core::*;
+use crate::rules::{PaddleMotion, Rules, Server};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Something noteworthy that happened during a step of the game.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Event {
+    /// A ball bounced off a wall or a paddle.
+    Bounce,
+    /// A ball's serve countdown ran out and it was put into play.
+    Serve,
+    /// A ball left the arena, scoring a point for the given `Side`.
+    Score(Side),
+    /// An extra ball joined a long rally.
+    ExtraBall,
+    /// A power-up appeared in the arena.
+    PowerUpAppeared,
+    /// A power-up was collected for the given `Side`.
+    PowerUp(PowerKind, Side),
+}
+
+/// The paddle axis values for a single step, and whether each side's serve
+/// was held.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Inputs {
+    pub left: Option<f32>,
+    pub right: Option<f32>,
+    pub top: Option<f32>,
+    pub bottom: Option<f32>,
+    /// Indexed by `Side::index`.
+    pub serve: [bool; 4],
+}
+
+impl Inputs {
+    pub fn for_side(&self, side: Side) -> Option<f32> {
+        match side {
+            Side::Left => self.left,
+            Side::Right => self.right,
+            Side::Top => self.top,
+            Side::Bottom => self.bottom,
+        }
+    }
+
+    pub fn serves(&self, side: Side) -> bool {
+        self.serve[side.index()]
+    }
+}
+
+/// A `Paddle` and the position of its centre.
+#[derive(Debug, Clone)]
+pub struct SimPaddle {
+    pub paddle: Paddle,
+    pub position: [f32; 2],
+}
+
+/// A `Ball` and the position of its centre.
+#[derive(Debug, Clone)]
+pub struct SimBall {
+    pub ball: Ball,
+    pub active: Active,
+    pub position: [f32; 2],
+}
+
+/// A `PowerUp` and the position of its centre.
+#[derive(Debug, Clone)]
+pub struct SimPowerUp {
+    pub power_up: PowerUp,
+    pub position: [f32; 2],
+}
+
+/// A full match of Pong, without a window, renderer or audio device.
+#[derive(Debug, Clone)]
+pub struct PongSim {
+    pub rules: Rules,
+    pub arena: Arena,
+    pub paddles: Vec<SimPaddle>,
+    pub balls: Vec<SimBall>,
+    pub score_board: ScoreBoard,
+    pub rally: Rally,
+    pub power_ups: Vec<SimPowerUp>,
+    pub effects: Effects,
+    pub power_up_timer: PowerUpTimer,
+    pub random: Random,
+}
+
+impl PongSim {
+    /// A fresh match, laid out the same way as the `Pong` state.
+    pub fn new(rules: Rules, arena: Arena, players: Players, seed: u64) -> PongSim {
+        let mut random = Random::new(seed);
+        let sides = players.sides();
+        let paddles = Side::ALL
+            .iter()
+            .filter_map(|side| {
+                players.controller(*side).map(|controller| SimPaddle {
+                    paddle: Paddle::new(*side, controller, &rules),
+                    position: paddle_start(*side, &arena),
+                })
+            })
+            .collect();
+
+        let balls = (0..rules.balls)
+            .map(|i| serve_ball(&rules, &arena, i, &sides, &mut random))
+            .collect();
+
+        let score_board = ScoreBoard::new(rules.handicaps.head_start(&sides));
+
+        PongSim {
+            rules,
+            arena,
+            paddles,
+            balls,
+            score_board,
+            rally: Rally::default(),
+            power_ups: Vec::new(),
+            effects: Effects::default(),
+            power_up_timer: PowerUpTimer::default(),
+            random,
+        }
+    }
+
+    /// Advance the match by `dt` seconds.
+    pub fn step(&mut self, dt: f32, inputs: &Inputs) -> Vec<Event> {
+        let mut events = Vec::new();
+
+        self.score_board.duration += dt;
+        tick_effects(&mut self.effects, dt);
+        // Power-ups can slow the balls down.
+        let ball_dt = dt * ball_time_scale(&self.effects);
+        let sides: Vec<Side> = self.paddles.iter().map(|p| p.paddle.side).collect();
+
+        for sim_paddle in self.paddles.iter_mut() {
+            apply_effects(&self.effects, &mut sim_paddle.paddle);
+
+            let movement = match sim_paddle.paddle.controller {
+                Controller::Human => inputs.for_side(sim_paddle.paddle.side),
+                Controller::Computer => Some(computer_input(
+                    &self.rules,
+                    &self.arena,
+                    &sim_paddle.paddle,
+                    sim_paddle.position,
+                    self.balls.iter().map(|b| (&b.ball, b.position)),
+                )),
+            };
+
+            sim_paddle.position = move_paddle(
+                &self.arena,
+                &mut sim_paddle.paddle,
+                sim_paddle.position,
+                movement.unwrap_or(0.0),
+                dt,
+                &sides,
+            );
+        }
+
+        for sim_ball in self.balls.iter_mut() {
+            let held_by = sim_ball.active.held_by;
+
+            if let Some(server) = self.paddles.iter().find(|p| Some(p.paddle.side) == held_by) {
+                if hold_serve(
+                    &self.rules,
+                    &sim_ball.ball,
+                    &mut sim_ball.active,
+                    &mut sim_ball.position,
+                    &server.paddle,
+                    server.position,
+                    inputs,
+                    dt,
+                ) {
+                    events.push(Event::Serve);
+                }
+            }
+        }
+
+        for sim_ball in self.balls.iter_mut() {
+            if advance_ball(
+                &self.rules,
+                &mut sim_ball.ball,
+                &mut sim_ball.active,
+                &mut sim_ball.position,
+                ball_dt,
+            ) {
+                events.push(Event::Serve);
+            }
+        }
+
+        for sim_ball in self.balls.iter_mut() {
+            let start = last_position(&sim_ball.ball, sim_ball.position, ball_dt);
+
+            if bounce_off_arena(&mut sim_ball.ball, sim_ball.position, &self.arena, &sides) {
+                events.push(Event::Bounce);
+            }
+
+            for sim_paddle in self.paddles.iter() {
+                if bounce_off_paddle(
+                    &self.rules,
+                    &mut sim_ball.ball,
+                    start,
+                    &mut sim_ball.position,
+                    &sim_paddle.paddle,
+                    sim_paddle.position,
+                ) {
+                    count_hit(&mut self.rally, &mut self.score_board);
+                    events.push(Event::Bounce);
+                }
+            }
+
+            if sim_ball.active.countdown.is_none() {
+                sim_ball.ball.ramp(ball_dt);
+            }
+
+            track_speed(&sim_ball.ball, &mut self.score_board);
+        }
+
+        let mut split_balls = Vec::new();
+
+        for sim_ball in self.balls.iter() {
+            let rules = &self.rules;
+            let effects = &mut self.effects;
+
+            self.power_ups.retain(|sim_power_up| {
+                let collected = touches(
+                    &sim_ball.ball,
+                    sim_ball.position,
+                    &sim_power_up.power_up,
+                    sim_power_up.position,
+                );
+
+                match sim_ball.ball.last_hit {
+                    Some(side) if collected => {
+                        let kind = sim_power_up.power_up.kind;
+                        events.push(Event::PowerUp(kind, side));
+                        split_balls.extend(collect_power_up(
+                            rules,
+                            kind,
+                            side,
+                            &sim_ball.ball,
+                            sim_ball.position,
+                            effects,
+                        ));
+                        false
+                    }
+                    _ => true,
+                }
+            });
+        }
+
+        self.balls.extend(split_balls);
+
+        let mut scored = Vec::new();
+
+        for (i, sim_ball) in self.balls.iter_mut().enumerate() {
+            if let Some(side) = score(
+                &self.rules,
+                &self.arena,
+                &mut sim_ball.ball,
+                &mut sim_ball.active,
+                sim_ball.position,
+                &mut self.score_board,
+                &sides,
+                &mut self.random,
+            ) {
+                self.rally = Rally::default();
+                events.push(Event::Score(side));
+                scored.push(i);
+            }
+        }
+
+        // Extra balls that scored leave the arena.
+        let mut i = 0;
+        self.balls.retain(|sim_ball| {
+            let keep = !(sim_ball.ball.extra && scored.contains(&i));
+            i += 1;
+            keep
+        });
+
+        if extend_rally(&self.rules, &mut self.rally, dt, self.balls.len()) {
+            self.balls.push(extra_ball(
+                &self.rules,
+                &self.arena,
+                &sides,
+                &mut self.random,
+            ));
+            events.push(Event::ExtraBall);
+        }
+
+        if let Some(power_up) = power_up_due(
+            &self.rules,
+            &self.arena,
+            &mut self.power_up_timer,
+            dt,
+            self.power_ups.len(),
+        ) {
+            self.power_ups.push(power_up);
+            events.push(Event::PowerUpAppeared);
+        }
+
+        events
+    }
+
+    /// The winner of the match, if it's over.
+    pub fn winner(&self) -> Option<Side> {
+        self.score_board.winner(&self.rules)
+    }
+}
+
+impl Default for PongSim {
+    fn default() -> PongSim {
+        PongSim::new(Rules::default(), Arena::default(), Players::default(), 0)
+    }
+}
+
+/// Where a `Paddle` on the given `Side` begins the match.
+pub fn paddle_start(side: Side, arena: &Arena) -> [f32; 2] {
+    let [x, y] = arena.centre();
+
+    match side {
+        Side::Left => [PADDLE_WIDTH * 0.5, y],
+        Side::Right => [arena.width - PADDLE_WIDTH * 0.5, y],
+        Side::Top => [x, arena.height - PADDLE_WIDTH * 0.5],
+        Side::Bottom => [x, PADDLE_WIDTH * 0.5],
+    }
+}
+
+/// Paddles on the top and bottom walls work just like those on the right and
+/// left, once x and y are swapped. This swaps them, for those paddles only.
+fn orient(side: Side, point: [f32; 2]) -> [f32; 2] {
+    if side.horizontal() {
+        [point[1], point[0]]
+    } else {
+        point
+    }
+}
+
+/// The side on the left or right wall that `side` works like, once oriented.
+fn upright(side: Side) -> Side {
+    match side {
+        Side::Top => Side::Right,
+        Side::Bottom => Side::Left,
+        side => side,
+    }
+}
+
+/// The `index`th ball of a fresh match. The first is held on the paddle of
+/// whoever serves first, like any other serve. The rest are spread out down
+/// the middle of the arena, and served toward any of the `sides` in play.
+pub fn serve_ball(
+    rules: &Rules,
+    arena: &Arena,
+    index: u32,
+    sides: &[Side],
+    random: &mut Random,
+) -> SimBall {
+    if index == 0 {
+        let server = first_server(rules, sides, random);
+        let position = paddle_start(server, arena);
+        let mut serve = served(rules, position, false, server.opposite(), random);
+        serve.active.held_by = Some(server);
+        return serve;
+    }
+
+    let y = arena.height * (index + 1) as f32 / (rules.balls + 1) as f32;
+    let toward = sides[random.rng.gen_range(0..sides.len())];
+    served(rules, [arena.width / 2.0, y], false, toward, random)
+}
+
+/// An extra ball to join a rally, served from the centre.
+pub fn extra_ball(rules: &Rules, arena: &Arena, sides: &[Side], random: &mut Random) -> SimBall {
+    let toward = sides[random.rng.gen_range(0..sides.len())];
+    served(rules, arena.centre(), true, toward, random)
+}
+
+fn served(
+    rules: &Rules,
+    position: [f32; 2],
+    extra: bool,
+    toward: Side,
+    random: &mut Random,
+) -> SimBall {
+    let mut ball = Ball::new(rules);
+    ball.extra = extra;
+    ball.velocity = serve_velocity(rules, toward, random);
+
+    SimBall {
+        ball,
+        active: Active {
+            countdown: Some(rules.serve_countdown),
+            held_by: None,
+        },
+        position,
+    }
+}
+
+/// Who serves the opening point. One of the `sides` is drawn as though they'd
+/// just lost a point, and the serving rule takes it from there on an empty
+/// board.
+pub fn first_server(rules: &Rules, sides: &[Side], random: &mut Random) -> Side {
+    let loser = sides[random.rng.gen_range(0..sides.len())];
+    next_server(
+        rules,
+        loser.opposite(),
+        loser,
+        &ScoreBoard::default(),
+        sides,
+    )
+}
+
+/// The velocity of a fresh serve toward the wall on `toward`, at an angle
+/// somewhere within the serve cone to either side.
+pub fn serve_velocity(rules: &Rules, toward: Side, random: &mut Random) -> [f32; 2] {
+    let spread = rules.serve_spread;
+    let angle = if spread > 0.0 {
+        random
+            .rng
+            .gen_range(rules.serve_angle - spread..=rules.serve_angle + spread)
+    } else {
+        rules.serve_angle
+    }
+    .to_radians();
+
+    let sign = if random.rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+    let speed = rules.ball_speed.base;
+    let direction = match upright(toward) {
+        Side::Left => -1.0,
+        _ => 1.0,
+    };
+
+    orient(
+        toward,
+        [direction * speed * angle.cos(), sign * speed * angle.sin()],
+    )
+}
+
+/// Count a paddle hit toward the rally in progress, and the longest of the
+/// match.
+pub fn count_hit(rally: &mut Rally, score_board: &mut ScoreBoard) {
+    rally.hits += 1;
+    score_board.longest_rally = score_board.longest_rally.max(rally.hits);
+}
+
+/// Note how fast `ball` is going, should it be the fastest of the match.
+pub fn track_speed(ball: &Ball, score_board: &mut ScoreBoard) {
+    score_board.top_speed = score_board.top_speed.max(ball.speed());
+}
+
+/// Move the rally clock on by `dt` seconds. Yields `true` if an extra ball
+/// should now join, with `balls` already in play.
+pub fn extend_rally(rules: &Rules, rally: &mut Rally, dt: f32, balls: usize) -> bool {
+    rally.time += dt;
+
+    match rules.extra_balls {
+        Some(extra) => {
+            let due = (rally.time / extra.every) as u32;
+
+            if due > rally.extra_balls && (balls as u32) < extra.max {
+                rally.extra_balls += 1;
+                true
+            } else {
+                false
+            }
+        }
+        None => false,
+    }
+}
+
+/// The new position of a paddle after pushing it along its wall by an input
+/// axis `amount` for `dt` seconds. Positive amounts are up or to the right.
+/// How quickly it answers is up to the paddle's own `motion`. It stops short
+/// of any corner blocks between the `sides` in play, and its velocity is kept
+/// up to date along the way.
+pub fn move_paddle(
+    arena: &Arena,
+    paddle: &mut Paddle,
+    position: [f32; 2],
+    amount: f32,
+    dt: f32,
+    sides: &[Side],
+) -> [f32; 2] {
+    let [across, along] = orient(paddle.side, position);
+    let (length, low, high) = if paddle.side.horizontal() {
+        (arena.width, Side::Left, Side::Right)
+    } else {
+        (arena.height, Side::Bottom, Side::Top)
+    };
+    let block = |end| {
+        if sides.contains(&end) && sides.contains(&paddle.side) {
+            CORNER
+        } else {
+            0.0
+        }
+    };
+    // A paddle too long for its wall is kept in the middle of it.
+    let reach = (paddle.height * 0.5).min((length - block(low) - block(high)) * 0.5);
+    let wanted = paddle.motion.max_speed() * paddle.speed * amount;
+    let velocity = match paddle.motion {
+        PaddleMotion::Instant { .. } => wanted,
+        PaddleMotion::Inertia {
+            acceleration,
+            friction,
+            ..
+        } => {
+            // Letting go leaves only friction to bring the paddle to rest.
+            let (target, rate) = if amount == 0.0 {
+                (0.0, friction)
+            } else {
+                (wanted, acceleration)
+            };
+            let change = (target - paddle.velocity).clamp(-rate * dt, rate * dt);
+            paddle.velocity + change
+        }
+    };
+    let moved = (along + velocity * dt).clamp(block(low) + reach, length - block(high) - reach);
+    paddle.velocity = (moved - along) / dt;
+
+    orient(paddle.side, [across, moved])
+}
+
+/// The input axis value the computer gives a `Paddle`. It chases the nearest
+/// ball heading its way, and drifts back to the centre otherwise.
+pub fn computer_input<'a, I>(
+    rules: &Rules,
+    arena: &Arena,
+    paddle: &Paddle,
+    position: [f32; 2],
+    balls: I,
+) -> f32
+where
+    I: Iterator<Item = (&'a Ball, [f32; 2])>,
+{
+    let position = orient(paddle.side, position);
+    let incoming = balls
+        .filter(|(ball, _)| {
+            let velocity = orient(paddle.side, ball.velocity);
+
+            match upright(paddle.side) {
+                Side::Left => velocity[0] < 0.0,
+                _ => velocity[0] > 0.0,
+            }
+        })
+        .map(|(_, ball_position)| orient(paddle.side, ball_position))
+        .min_by(|a, b| {
+            let da = (a[0] - position[0]).abs();
+            let db = (b[0] - position[0]).abs();
+            da.total_cmp(&db)
+        });
+
+    let centre = paddle_start(paddle.side, arena);
+    let target = incoming
+        .map(|p| p[1])
+        .unwrap_or(orient(paddle.side, centre)[1]);
+    let distance = (target - position[1]) / (paddle.height * 0.5);
+
+    distance.clamp(-1.0, 1.0) * rules.computer_speed
+}
+
+/// Move a ball along its velocity, curving with any spin, or count down its
+/// serve. Yields `true` if the ball was served on this step.
+pub fn advance_ball(
+    rules: &Rules,
+    ball: &mut Ball,
+    active: &mut Active,
+    position: &mut [f32; 2],
+    dt: f32,
+) -> bool {
+    // A ball held on a paddle is served by `hold_serve` instead.
+    if active.held_by.is_some() {
+        return false;
+    }
+
+    match active.countdown {
+        None => {
+            curve(rules, ball, dt);
+            position[0] += ball.velocity[0] * dt;
+            position[1] += ball.velocity[1] * dt;
+            false
+        }
+        Some(timer) if timer - dt < 0.0 => {
+            active.countdown.take();
+            true
+        }
+        Some(timer) => {
+            active.countdown.replace(timer - dt);
+            false
+        }
+    }
+}
+
+/// Turn a ball's velocity by its spin over `dt` seconds, and let the spin
+/// wear off.
+fn curve(rules: &Rules, ball: &mut Ball, dt: f32) {
+    if ball.spin == 0.0 {
+        return;
+    }
+
+    let (sin, cos) = (ball.spin * dt).sin_cos();
+    let [x, y] = ball.velocity;
+    ball.velocity = [x * cos - y * sin, x * sin + y * cos];
+
+    let decay = rules.spin.map(|spin| spin.decay).unwrap_or(1.0);
+    ball.spin *= (1.0 - decay).powf(dt);
+}
+
+/// Keep a ball that's waiting to be served in front of its server's paddle.
+/// It's launched once the countdown runs out or, when serves are manual and
+/// the server is human, once they serve themselves. Yields `true` on launch.
+#[allow(clippy::too_many_arguments)]
+pub fn hold_serve(
+    rules: &Rules,
+    ball: &Ball,
+    active: &mut Active,
+    position: &mut [f32; 2],
+    paddle: &Paddle,
+    paddle_position: [f32; 2],
+    inputs: &Inputs,
+    dt: f32,
+) -> bool {
+    let offset = paddle.width * 0.5 + ball.radius;
+    let [x, y] = orient(paddle.side, paddle_position);
+    let x = match upright(paddle.side) {
+        Side::Left => x + offset,
+        _ => x - offset,
+    };
+    *position = orient(paddle.side, [x, y]);
+
+    let launch = if rules.manual_serve && paddle.controller == Controller::Human {
+        inputs.serves(paddle.side)
+    } else {
+        let timer = active.countdown.unwrap_or(0.0) - dt;
+        active.countdown.replace(timer);
+        timer < 0.0
+    };
+
+    if launch {
+        active.countdown = None;
+        active.held_by = None;
+    }
+
+    launch
+}
+
+/// Who serves next, now that `scorer` has won the point just recorded on the
+/// `score_board`, and `loser` let the ball through. Serving in turn goes
+/// round the `sides` in play. A wall can't serve, so in practice the loser
+/// always does.
+pub fn next_server(
+    rules: &Rules,
+    scorer: Side,
+    loser: Side,
+    score_board: &ScoreBoard,
+    sides: &[Side],
+) -> Side {
+    match rules.server {
+        Server::Loser => loser,
+        Server::Winner if sides.contains(&scorer) => scorer,
+        Server::Winner => loser,
+        Server::Alternate(every) => {
+            // Each new game also passes the serve on.
+            let points: u32 = score_board.points.iter().sum();
+            let games: u32 = score_board.games.iter().sum();
+
+            sides[(points / every + games) as usize % sides.len()]
+        }
+    }
+}
+
+/// The sides of the `paddles` in play, in the order `Players::sides` gives
+/// them, whatever order the paddles come in. Serving in turn depends on it.
+pub fn sides_of<'a>(paddles: impl IntoIterator<Item = &'a Paddle>) -> Vec<Side> {
+    let mut sides: Vec<Side> = paddles.into_iter().map(|paddle| paddle.side).collect();
+    sides.sort_by_key(|side| side.index());
+    sides
+}
+
+/// The corner blocks, one wherever two of the `sides` in play meet. Each is
+/// `CORNER` across.
+pub fn corners(arena: &Arena, sides: &[Side]) -> Vec<Obstacle> {
+    let low = CORNER * 0.5;
+    let (right, top) = (arena.width - low, arena.height - low);
+
+    [
+        (Side::Left, Side::Bottom, [low, low]),
+        (Side::Left, Side::Top, [low, top]),
+        (Side::Right, Side::Bottom, [right, low]),
+        (Side::Right, Side::Top, [right, top]),
+    ]
+    .iter()
+    .filter(|(a, b, _)| sides.contains(a) && sides.contains(b))
+    .map(|(_, _, centre)| Obstacle::Rect {
+        centre: *centre,
+        size: [CORNER, CORNER],
+    })
+    .collect()
+}
+
+/// Bounce a ball off the arena itself: its walls, except where they open onto
+/// the goal of one of the `sides` in play, any corner blocks, and its
+/// obstacles. Yields `true` on a bounce.
+pub fn bounce_off_arena(
+    ball: &mut Ball,
+    position: [f32; 2],
+    arena: &Arena,
+    sides: &[Side],
+) -> bool {
+    let [x, y] = position;
+    let r = ball.radius;
+    let mut bounced = false;
+
+    for obstacle in corners(arena, sides).iter().chain(arena.obstacles.iter()) {
+        bounced |= bounce_off_obstacle(ball, position, obstacle);
+    }
+
+    for side in Side::ALL.iter() {
+        let (axis, along, hit) = match side {
+            Side::Left => (0, y, x <= r && ball.velocity[0] < 0.0),
+            Side::Right => (0, y, x >= arena.width - r && ball.velocity[0] > 0.0),
+            Side::Top => (1, x, y >= arena.height - r && ball.velocity[1] > 0.0),
+            Side::Bottom => (1, x, y <= r && ball.velocity[1] < 0.0),
+        };
+        let open = sides.contains(side) && arena.in_goal(*side, along);
+
+        if hit && !open {
+            ball.velocity[axis] *= -1.0;
+            bounced = true;
+        }
+    }
+
+    bounced
+}
+
+/// Bounce a ball off a single obstacle, if it has run into it. Yields `true`
+/// on a bounce.
+fn bounce_off_obstacle(ball: &mut Ball, position: [f32; 2], obstacle: &Obstacle) -> bool {
+    let r = ball.radius;
+
+    match *obstacle {
+        Obstacle::Rect { centre, size } => {
+            let reach = [size[0] * 0.5 + r, size[1] * 0.5 + r];
+            let gap = [position[0] - centre[0], position[1] - centre[1]];
+
+            if gap[0].abs() >= reach[0] || gap[1].abs() >= reach[1] {
+                return false;
+            }
+
+            // How far the ball has sunk into the block through each pair of
+            // faces. It's turned back through whichever it sank into least.
+            let axis = if reach[0] - gap[0].abs() < reach[1] - gap[1].abs() {
+                0
+            } else {
+                1
+            };
+            let outward = gap[axis].signum();
+
+            if ball.velocity[axis] * outward < 0.0 {
+                ball.velocity[axis] *= -1.0;
+                true
+            } else {
+                false
+            }
+        }
+        Obstacle::Circle { centre, radius } => {
+            let gap = [position[0] - centre[0], position[1] - centre[1]];
+            let distance = gap[0].hypot(gap[1]);
+
+            if distance >= radius + r || distance == 0.0 {
+                return false;
+            }
+
+            // Reflect the ball's velocity about the post's surface, unless it
+            // is already on its way out.
+            let normal = [gap[0] / distance, gap[1] / distance];
+            let dot = ball.velocity[0] * normal[0] + ball.velocity[1] * normal[1];
+
+            if dot < 0.0 {
+                ball.velocity[0] -= 2.0 * dot * normal[0];
+                ball.velocity[1] -= 2.0 * dot * normal[1];
+                true
+            } else {
+                false
+            }
+        }
+    }
+}
+
+/// Take a life for a ball lost in breakout. Extra balls come and go freely.
+pub fn lose_life(lives: &mut Lives, ball: &Ball) {
+    if !ball.extra {
+        lives.0 = lives.0.saturating_sub(1);
+    }
+}
+
+/// Bounce a ball off a brick, if it has run into it. Yields `true` on a
+/// bounce.
+pub fn bounce_off_brick(
+    ball: &mut Ball,
+    position: [f32; 2],
+    brick: &Brick,
+    brick_position: [f32; 2],
+) -> bool {
+    let block = Obstacle::Rect {
+        centre: brick_position,
+        size: brick.size,
+    };
+
+    bounce_off_obstacle(ball, position, &block)
+}
+
+/// Knock a hit off a brick. Yields `true` once it breaks.
+pub fn hit_brick(brick: &mut Brick) -> bool {
+    brick.hits = brick.hits.saturating_sub(1);
+    brick.hits == 0
+}
+
+/// Where a ball was at the start of a step of `dt` seconds.
+pub fn last_position(ball: &Ball, position: [f32; 2], dt: f32) -> [f32; 2] {
+    [
+        position[0] - ball.velocity[0] * dt,
+        position[1] - ball.velocity[1] * dt,
+    ]
+}
+
+/// Bounce a ball off a paddle, checking the whole path it took from `start`
+/// so that fast balls can't pass through. On a hit the ball is placed against
+/// the paddle's face, and the further from the paddle's centre it strikes,
+/// the steeper the angle it leaves at. A moving paddle carries the ball along
+/// with it, and may put spin on it too. Yields `true` on a bounce.
+pub fn bounce_off_paddle(
+    rules: &Rules,
+    ball: &mut Ball,
+    start: [f32; 2],
+    position: &mut [f32; 2],
+    paddle: &Paddle,
+    paddle_position: [f32; 2],
+) -> bool {
+    // Everything below is worked out as if for a paddle on the left or right.
+    let side = upright(paddle.side);
+    let start = orient(paddle.side, start);
+    let end = orient(paddle.side, *position);
+    let paddle_position = orient(paddle.side, paddle_position);
+    let velocity = orient(paddle.side, ball.velocity);
+
+    let approaching = match side {
+        Side::Left => velocity[0] < 0.0,
+        _ => velocity[0] > 0.0,
+    };
+
+    if !approaching {
+        return false;
+    }
+
+    // The paddle grown by the ball's radius, so that the ball can be treated
+    // as a single point.
+    let left = paddle_position[0] - paddle.width * 0.5 - ball.radius;
+    let right = paddle_position[0] + paddle.width * 0.5 + ball.radius;
+    let bottom = paddle_position[1] - paddle.height * 0.5 - ball.radius;
+    let top = paddle_position[1] + paddle.height * 0.5 + ball.radius;
+
+    let face = match side {
+        Side::Left => right,
+        _ => left,
+    };
+
+    // A ball that was already overlapping the paddle, say because the paddle
+    // moved into it, is pushed out through the face.
+    let contact = swept_contact(start, end, face, bottom, top).or_else(|| {
+        if point_in_rect(end[0], end[1], left, bottom, right, top) {
+            Some([face, end[1]])
+        } else {
+            None
+        }
+    });
+
+    match contact {
+        None => false,
+        Some(contact) => {
+            let angle = deflection(rules, ball, contact, paddle, paddle_position);
+            let speed = ball.speed_after_hit();
+            let direction = match side {
+                Side::Left => 1.0,
+                _ => -1.0,
+            };
+
+            // Part of the paddle's own velocity goes into the ball, though it
+            // still can't leave any steeper or faster than the rules allow.
+            let across = speed * angle.cos();
+            let along = speed * angle.sin() + rules.momentum * paddle.velocity;
+            let steepest = rules.max_deflection.to_radians();
+            let angle = along.atan2(across).clamp(-steepest, steepest);
+            let speed = across.hypot(along).min(ball.speed_curve.max);
+
+            ball.velocity = orient(
+                paddle.side,
+                [direction * speed * angle.cos(), speed * angle.sin()],
+            );
+            ball.spin = match rules.spin {
+                Some(spin) => {
+                    // Turning back against the paddle's motion. Swapping x and
+                    // y mirrors the turn, so it's mirrored back for the top
+                    // and bottom.
+                    let turn = -direction * spin.grip.to_radians() * paddle.velocity;
+
+                    if paddle.side.horizontal() {
+                        -turn
+                    } else {
+                        turn
+                    }
+                }
+                None => 0.0,
+            };
+            ball.last_hit = Some(paddle.side);
+            *position = orient(paddle.side, contact);
+            true
+        }
+    }
+}
+
+/// The angle in radians, relative to the paddle's normal, that a ball leaves
+/// a paddle at after striking it at `position`. Everything is oriented as if
+/// the paddle were on the left or right.
+fn deflection(
+    rules: &Rules,
+    ball: &Ball,
+    position: [f32; 2],
+    paddle: &Paddle,
+    paddle_position: [f32; 2],
+) -> f32 {
+    // How far from the centre of the paddle it struck, from -1 at the bottom
+    // edge to 1 at the top.
+    let reach = paddle.height * 0.5 + ball.radius;
+    let offset = ((position[1] - paddle_position[1]) / reach).clamp(-1.0, 1.0);
+
+    offset * rules.max_deflection.to_radians()
+}
+
+/// Award a point if a ball has gone through the goal of one of the `sides` in
+/// play, and reset it for the next serve. The point goes to whoever hit the
+/// ball last, or to the opposite side for a ball nobody else touched. Yields
+/// the `Side` that scored, even when that's a wall, which keeps no score.
+#[allow(clippy::too_many_arguments)]
+pub fn score(
+    rules: &Rules,
+    arena: &Arena,
+    ball: &mut Ball,
+    active: &mut Active,
+    position: [f32; 2],
+    score_board: &mut ScoreBoard,
+    sides: &[Side],
+    random: &mut Random,
+) -> Option<Side> {
+    let [x, y] = position;
+    let r = ball.radius;
+
+    let loser = sides.iter().copied().find(|side| match side {
+        Side::Left => x <= r && arena.in_goal(*side, y),
+        Side::Right => x >= arena.width - r && arena.in_goal(*side, y),
+        Side::Top => y >= arena.height - r && arena.in_goal(*side, x),
+        Side::Bottom => y <= r && arena.in_goal(*side, x),
+    })?;
+
+    let scorer = match ball.last_hit {
+        Some(hitter) if hitter != loser => hitter,
+        _ => loser.opposite(),
+    };
+
+    if sides.contains(&scorer) {
+        score_board.point(scorer, rules);
+    }
+
+    // The ball is served afresh from the server's paddle, back at its
+    // original speed.
+    let server = next_server(rules, scorer, loser, score_board, sides);
+    ball.velocity = serve_velocity(rules, server.opposite(), random);
+    ball.last_hit = None;
+    ball.spin = 0.0;
+    active.countdown.replace(rules.serve_countdown);
+    active.held_by = Some(server);
+
+    Some(scorer)
+}
+
+/// Count every power-up effect down by `dt` seconds, dropping those that
+/// wear off.
+pub fn tick_effects(effects: &mut Effects, dt: f32) {
+    for effect in effects.0.iter_mut() {
+        effect.remaining -= dt;
+    }
+
+    effects.0.retain(|effect| effect.remaining > 0.0);
+}
+
+/// Set a paddle's height and speed from the effects in force on its side.
+pub fn apply_effects(effects: &Effects, paddle: &mut Paddle) {
+    let mut height = paddle.base_height;
+    let mut speed = paddle.base_speed;
+
+    for effect in effects.0.iter().filter(|e| e.side == paddle.side) {
+        match effect.kind {
+            PowerKind::Grow => height *= GROW_FACTOR,
+            PowerKind::Shrink => height *= SHRINK_FACTOR,
+            PowerKind::Haste => speed *= HASTE_FACTOR,
+            PowerKind::SlowBall | PowerKind::SplitBall => (),
+        }
+    }
+
+    paddle.height = height;
+    paddle.speed = speed;
+}
+
+/// How fast time passes for the balls, given the effects in force.
+pub fn ball_time_scale(effects: &Effects) -> f32 {
+    if effects.0.iter().any(|e| e.kind == PowerKind::SlowBall) {
+        SLOW_BALL_FACTOR
+    } else {
+        1.0
+    }
+}
+
+/// Move the power-up clock on by `dt` seconds. Yields a new power-up if one
+/// is due, with `waiting` already in the arena.
+pub fn power_up_due(
+    rules: &Rules,
+    arena: &Arena,
+    timer: &mut PowerUpTimer,
+    dt: f32,
+    waiting: usize,
+) -> Option<SimPowerUp> {
+    let config = rules.power_ups?;
+
+    // The clock stops while the arena is full.
+    if waiting as u32 >= config.max {
+        return None;
+    }
+
+    timer.time += dt;
+
+    if timer.time >= config.every {
+        timer.time -= config.every;
+        timer.spawned += 1;
+        Some(nth_power_up(arena, timer.spawned - 1))
+    } else {
+        None
+    }
+}
+
+/// The `n`th power-up of a match. Each kind takes its turn, at spots around
+/// the middle of the arena that are clear of the paddles.
+fn nth_power_up(arena: &Arena, n: u32) -> SimPowerUp {
+    let spots = [[0.35, 0.7], [0.65, 0.3], [0.65, 0.7], [0.35, 0.3]];
+    let kinds = PowerKind::ALL;
+    let [x, y] = spots[n as usize % spots.len()];
+
+    SimPowerUp {
+        power_up: PowerUp {
+            kind: kinds[n as usize % kinds.len()],
+            radius: POWER_UP_RADIUS,
+        },
+        position: [arena.width * x, arena.height * y],
+    }
+}
+
+/// Whether a ball at `position` is passing through a power-up.
+pub fn touches(
+    ball: &Ball,
+    position: [f32; 2],
+    power_up: &PowerUp,
+    power_up_position: [f32; 2],
+) -> bool {
+    let dx = position[0] - power_up_position[0];
+    let dy = position[1] - power_up_position[1];
+
+    dx.hypot(dy) <= ball.radius + power_up.radius
+}
+
+/// Start the effect of a power-up that `ball` collected for `side`. A split
+/// ball happens straight away, yielding the new ball.
+pub fn collect_power_up(
+    rules: &Rules,
+    kind: PowerKind,
+    side: Side,
+    ball: &Ball,
+    position: [f32; 2],
+    effects: &mut Effects,
+) -> Option<SimBall> {
+    if kind == PowerKind::SplitBall {
+        let mut split = ball.clone();
+        // Mirrored, spin and all.
+        split.velocity[1] *= -1.0;
+        split.spin *= -1.0;
+        split.extra = true;
+
+        return Some(SimBall {
+            ball: split,
+            active: Active {
+                countdown: None,
+                held_by: None,
+            },
+            position,
+        });
+    }
+
+    let duration = rules.power_ups.map(|p| p.duration).unwrap_or(0.0);
+
+    // Collecting the same power-up again starts its clock over.
+    match effects
+        .0
+        .iter_mut()
+        .find(|e| e.kind == kind && e.side == side)
+    {
+        Some(effect) => effect.remaining = duration,
+        None => effects.0.push(Effect {
+            kind,
+            side,
+            remaining: duration,
+        }),
+    }
+
+    None
+}
+
+/// Where a point moving from `start` to `end` crosses the vertical line at
+/// `face`, if it does so between `bottom` and `top`.
+fn swept_contact(
+    start: [f32; 2],
+    end: [f32; 2],
+    face: f32,
+    bottom: f32,
+    top: f32,
+) -> Option<[f32; 2]> {
+    let dx = end[0] - start[0];
+
+    if dx == 0.0 {
+        return None;
+    }
+
+    let t = (face - start[0]) / dx;
+    let y = start[1] + (end[1] - start[1]) * t;
+
+    if (0.0..=1.0).contains(&t) && y >= bottom && y <= top {
+        Some([face, y])
+    } else {
+        None
+    }
+}
+
+fn point_in_rect(x: f32, y: f32, left: f32, bottom: f32, right: f32, top: f32) -> bool {
+    x >= left && x <= right && y >= bottom && y <= top
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn computers() -> Players {
+        Players {
+            left: Controller::Computer,
+            right: Some(Controller::Computer),
+            top: None,
+            bottom: None,
+        }
+    }
+
+    #[test]
+    fn default_match_has_a_winner() {
+        let mut sim = PongSim::new(Rules::default(), Arena::default(), computers(), 1);
+        let dt = 1.0 / sim.rules.steps_per_second as f32;
+        let inputs = Inputs::default();
+
+        // Half an hour of play is far more than a match to ten takes.
+        for _ in 0..sim.rules.steps_per_second * 60 * 30 {
+            if sim.winner().is_some() {
+                break;
+            }
+
+            sim.step(dt, &inputs);
+        }
+
+        assert!(sim.winner().is_some());
+    }
+
+    #[test]
+    fn fast_balls_dont_pass_through_paddles() {
+        let rules = Rules::default();
+        let arena = Arena::default();
+        let paddle = Paddle::new(Side::Left, Controller::Human, &rules);
+        let paddle_position = paddle_start(Side::Left, &arena);
+        let mut ball = Ball::new(&rules);
+        ball.velocity = [-rules.ball_speed.max, 0.0];
+
+        // A long step, over which the ball travels further than the paddle is wide.
+        let dt = 0.1;
+        let start = [
+            paddle_position[0] + PADDLE_WIDTH + ball.radius,
+            paddle_position[1],
+        ];
+        let mut position = [start[0] + ball.velocity[0] * dt, start[1]];
+        assert!(position[0] < paddle_position[0] - PADDLE_WIDTH);
+
+        assert!(bounce_off_paddle(
+            &rules,
+            &mut ball,
+            start,
+            &mut position,
+            &paddle,
+            paddle_position,
+        ));
+        assert!(ball.velocity[0] > 0.0);
+        assert!(position[0] >= paddle_position[0] + PADDLE_WIDTH * 0.5 + ball.radius);
+    }
+
+    #[test]
+    fn alternate_serves_rotate() {
+        let rules = Rules {
+            server: Server::Alternate(2),
+            ..Rules::default()
+        };
+        let sides = [Side::Left, Side::Right];
+        let mut board = ScoreBoard::default();
+        let mut servers = Vec::new();
+
+        for point in 0..6 {
+            board.points[point % 2] += 1;
+            servers.push(next_server(&rules, Side::Left, Side::Right, &board, &sides));
+        }
+
+        assert_eq!(
+            servers,
+            vec![
+                Side::Left,
+                Side::Right,
+                Side::Right,
+                Side::Left,
+                Side::Left,
+                Side::Right,
+            ]
+        );
+
+        // A new game passes the serve on, whatever the points.
+        board.points = [0; 4];
+        board.games = [1, 0, 0, 0];
+        assert_eq!(
+            next_server(&rules, Side::Left, Side::Right, &board, &sides),
+            Side::Right
+        );
+    }
+
+    #[test]
+    fn opening_serve_waits_for_the_server() {
+        let rules = Rules {
+            server: Server::Alternate(2),
+            manual_serve: true,
+            ..Rules::default()
+        };
+        let mut sim = PongSim::new(rules, Arena::default(), Players::two_player(), 1);
+        let dt = 1.0 / sim.rules.steps_per_second as f32;
+
+        // Well past the countdown, the ball is still on the first server's paddle.
+        for _ in 0..sim.rules.steps_per_second * 5 {
+            sim.step(dt, &Inputs::default());
+        }
+        assert_eq!(sim.balls[0].active.held_by, Some(Side::Left));
+
+        let mut serve = Inputs::default();
+        serve.serve[Side::Left.index()] = true;
+        assert!(sim.step(dt, &serve).contains(&Event::Serve));
+        assert_eq!(sim.balls[0].active.held_by, None);
+        assert!(sim.balls[0].ball.velocity[0] > 0.0);
+    }
+
+    fn paddle_moving_by(motion: PaddleMotion) -> (Paddle, [f32; 2]) {
+        let mut rules = Rules::default();
+        rules.paddle_motion.default = motion;
+        let paddle = Paddle::new(Side::Left, Controller::Human, &rules);
+
+        (paddle, paddle_start(Side::Left, &Arena::default()))
+    }
+
+    #[test]
+    fn inertia_ramps_up_and_coasts() {
+        let motion = PaddleMotion::Inertia {
+            max_speed: 72.0,
+            acceleration: 600.0,
+            friction: 400.0,
+        };
+        let (mut paddle, mut position) = paddle_moving_by(motion);
+        let arena = Arena::default();
+        let sides = [Side::Left, Side::Right];
+        let dt = 0.01;
+
+        // A tenth of a second in, the paddle is still short of full speed.
+        for _ in 0..10 {
+            position = move_paddle(&arena, &mut paddle, position, 1.0, dt, &sides);
+        }
+        assert!((paddle.velocity - 60.0).abs() < 0.01);
+
+        for _ in 0..10 {
+            position = move_paddle(&arena, &mut paddle, position, 1.0, dt, &sides);
+        }
+        assert!((paddle.velocity - 72.0).abs() < 0.01);
+
+        // Let go, it slides on until friction brings it to rest.
+        let before = position[1];
+        for _ in 0..10 {
+            position = move_paddle(&arena, &mut paddle, position, 0.0, dt, &sides);
+        }
+        assert!((paddle.velocity - 32.0).abs() < 0.01);
+        assert!(position[1] > before);
+
+        for _ in 0..10 {
+            position = move_paddle(&arena, &mut paddle, position, 0.0, dt, &sides);
+        }
+        assert_eq!(paddle.velocity, 0.0);
+    }
+
+    #[test]
+    fn instant_moves_at_full_speed_at_once() {
+        let (mut paddle, start) = paddle_moving_by(PaddleMotion::Instant { max_speed: 72.0 });
+        let arena = Arena::default();
+        let sides = [Side::Left, Side::Right];
+        let dt = 0.01;
+
+        let moved = move_paddle(&arena, &mut paddle, start, 1.0, dt, &sides);
+        assert!((moved[1] - start[1] - 0.72).abs() < 0.0001);
+        assert!((paddle.velocity - 72.0).abs() < 0.01);
+
+        let stopped = move_paddle(&arena, &mut paddle, moved, 0.0, dt, &sides);
+        assert_eq!(stopped, moved);
+        assert_eq!(paddle.velocity, 0.0);
+    }
+}
@@ -1,11 +1,25 @@
 pub use bounce::BounceSystem;
+pub use bricks::BrickSystem;
+pub use collect_power_ups::CollectPowerUpSystem;
+pub use effects::EffectSystem;
 pub use fps::FpsSystem;
 pub use move_balls::MoveBallSystem;
 pub use paddle::PaddleSystem;
+pub use rally::RallySystem;
 pub use score::ScoreSystem;
+pub use serve::ServeSystem;
+pub use spawn_balls::SpawnBallSystem;
+pub use spawn_power_ups::SpawnPowerUpSystem;
 
 mod bounce;
+mod bricks;
+mod collect_power_ups;
+mod effects;
 mod fps;
 mod move_balls;
 mod paddle;
+mod rally;
 mod score;
+mod serve;
+mod spawn_balls;
+mod spawn_power_ups;
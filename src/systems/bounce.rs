@@ -1,72 +1,93 @@
+use crate::arena::Arena;
 use crate::audio;
 use crate::core::*;
+use crate::rules::Rules;
+use crate::sim;
 use amethyst::assets::AssetStorage;
 use amethyst::audio::output::Output;
 use amethyst::audio::Source;
 use amethyst::core::Transform;
-use amethyst::ecs::{Join, Read, ReadExpect, ReadStorage, System, WriteStorage};
-use std::ops::Deref;
+use amethyst::ecs::{Join, Read, ReadExpect, ReadStorage, System, Write, WriteStorage};
 
 pub struct BounceSystem;
 
 impl<'s> System<'s> for BounceSystem {
     type SystemData = (
         WriteStorage<'s, Ball>,
+        ReadStorage<'s, Active>,
         ReadStorage<'s, Paddle>,
-        ReadStorage<'s, Transform>,
+        WriteStorage<'s, Transform>,
         Read<'s, AssetStorage<Source>>,
         ReadExpect<'s, audio::Sounds>,
         Option<Read<'s, Output>>,
+        ReadExpect<'s, Rules>,
+        ReadExpect<'s, Arena>,
+        ReadExpect<'s, FixedStep>,
+        Read<'s, Effects>,
+        Write<'s, Rally>,
+        Write<'s, ScoreBoard>,
     );
 
     fn run(
         &mut self,
-        (mut balls, paddles, transforms, storage, sounds, audio_output): Self::SystemData,
+        (
+            mut balls,
+            actives,
+            paddles,
+            mut transforms,
+            storage,
+            sounds,
+            audio_output,
+            rules,
+            arena,
+            step,
+            effects,
+            mut rally,
+            mut score_board,
+        ): Self::SystemData,
     ) {
-        for (ball, transform) in (&mut balls, &transforms).join() {
-            let ball_x = transform.translation().x;
-            let ball_y = transform.translation().y;
-            let output = audio_output.as_ref().map(|o| o.deref());
+        let delta = step.dt * sim::ball_time_scale(&effects);
+        let mut paddles: Vec<(&Paddle, [f32; 2])> = (&paddles, &transforms)
+            .join()
+            .map(|(paddle, t)| (paddle, [t.translation().x, t.translation().y]))
+            .collect();
+        paddles.sort_by_key(|(paddle, _)| paddle.side.index());
+        let sides = sim::sides_of(paddles.iter().map(|(paddle, _)| *paddle));
 
-            // Bounce off the walls.
-            if (ball_y <= ball.radius && ball.velocity[1] < 0.0)
-                || (ball_y >= ARENA_HEIGHT - ball.radius && ball.velocity[1] > 0.0)
-            {
-                ball.velocity[1] *= -1.0;
+        for (ball, active, transform) in (&mut balls, &actives, &mut transforms).join() {
+            let mut position = [transform.translation().x, transform.translation().y];
+            let start = sim::last_position(ball, position, delta);
+            let output = audio_output.as_deref();
+
+            // Bounce off the walls and obstacles.
+            if sim::bounce_off_arena(ball, position, &arena, &sides) {
                 audio::play_bounce_sound(&sounds, &storage, output);
             }
 
             // Bounce off the paddles.
-            for (paddle, paddle_transform) in (&paddles, &transforms).join() {
-                // Location of the bottom corner of the paddle.
-                let paddle_x = paddle_transform.translation().x - (paddle.width * 0.5);
-                let paddle_y = paddle_transform.translation().y - (paddle.height * 0.5);
-
-                if point_in_rect(
-                    ball_x,
-                    ball_y,
-                    paddle_x - ball.radius,
-                    paddle_y - ball.radius,
-                    paddle_x + paddle.width + ball.radius,
-                    paddle_y + paddle.height + ball.radius,
+            for (paddle, paddle_position) in paddles.iter() {
+                if sim::bounce_off_paddle(
+                    &rules,
+                    ball,
+                    start,
+                    &mut position,
+                    paddle,
+                    *paddle_position,
                 ) {
-                    match paddle.side {
-                        Side::Left if ball.velocity[0] < 0.0 => {
-                            ball.velocity[0] *= -1.05;
-                            audio::play_bounce_sound(&sounds, &storage, output);
-                        }
-                        Side::Right if ball.velocity[0] > 0.0 => {
-                            ball.velocity[0] *= -1.05;
-                            audio::play_bounce_sound(&sounds, &storage, output);
-                        }
-                        _ => (),
-                    }
+                    sim::count_hit(&mut rally, &mut score_board);
+                    audio::play_bounce_sound(&sounds, &storage, output);
                 }
             }
+
+            // The longer the rally, the faster the ball.
+            if active.countdown.is_none() {
+                ball.ramp(delta);
+            }
+
+            sim::track_speed(ball, &mut score_board);
+
+            transform.set_translation_x(position[0]);
+            transform.set_translation_y(position[1]);
         }
     }
 }
-
-fn point_in_rect(x: f32, y: f32, left: f32, bottom: f32, right: f32, top: f32) -> bool {
-    x >= left && x <= right && y >= bottom && y <= top
-}
@@ -0,0 +1,47 @@
+use crate::audio;
+use crate::core::{Ball, Brick};
+use crate::sim;
+use amethyst::assets::AssetStorage;
+use amethyst::audio::output::Output;
+use amethyst::audio::Source;
+use amethyst::core::Transform;
+use amethyst::ecs::{Entities, Join, Read, ReadExpect, ReadStorage, System, WriteStorage};
+
+/// Bounces balls off the bricks in breakout, breaking them as they go.
+pub struct BrickSystem;
+
+impl<'s> System<'s> for BrickSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Ball>,
+        WriteStorage<'s, Brick>,
+        ReadStorage<'s, Transform>,
+        Read<'s, AssetStorage<Source>>,
+        ReadExpect<'s, audio::Sounds>,
+        Option<Read<'s, Output>>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, mut balls, mut bricks, transforms, storage, sounds, audio_output): Self::SystemData,
+    ) {
+        for (ball, transform) in (&mut balls, &transforms).join() {
+            let position = [transform.translation().x, transform.translation().y];
+
+            for (entity, brick, brick_transform) in (&entities, &mut bricks, &transforms).join() {
+                let brick_position = [
+                    brick_transform.translation().x,
+                    brick_transform.translation().y,
+                ];
+
+                if brick.hits > 0 && sim::bounce_off_brick(ball, position, brick, brick_position) {
+                    if sim::hit_brick(brick) {
+                        let _ = entities.delete(entity);
+                    }
+
+                    audio::play_bounce_sound(&sounds, &storage, audio_output.as_deref());
+                }
+            }
+        }
+    }
+}
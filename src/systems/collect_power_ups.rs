@@ -0,0 +1,94 @@
+use crate::core::{Active, Ball, Effects, Interpolated, PowerUp, Sprites, BALL_SPRITE};
+use crate::rules::Rules;
+use crate::sim;
+use amethyst::core::Transform;
+use amethyst::ecs::{Entities, Join, ReadExpect, ReadStorage, System, Write, WriteStorage};
+use amethyst::renderer::SpriteRender;
+
+/// Hands power-ups to the last paddle to hit a ball that passes through them.
+pub struct CollectPowerUpSystem;
+
+impl<'s> System<'s> for CollectPowerUpSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Ball>,
+        ReadStorage<'s, PowerUp>,
+        WriteStorage<'s, Active>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, SpriteRender>,
+        WriteStorage<'s, Interpolated>,
+        Write<'s, Effects>,
+        ReadExpect<'s, Rules>,
+        ReadExpect<'s, Sprites>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut balls,
+            power_ups,
+            mut actives,
+            mut transforms,
+            mut sprites,
+            mut interpolated,
+            mut effects,
+            rules,
+            sheet,
+        ): Self::SystemData,
+    ) {
+        let mut collected = Vec::new();
+        let mut split_balls = Vec::new();
+
+        for (ball, transform) in (&balls, &transforms).join() {
+            let side = match ball.last_hit {
+                Some(side) => side,
+                None => continue,
+            };
+            let position = [transform.translation().x, transform.translation().y];
+
+            for (entity, power_up, power_up_transform) in
+                (&entities, &power_ups, &transforms).join()
+            {
+                let power_up_position = [
+                    power_up_transform.translation().x,
+                    power_up_transform.translation().y,
+                ];
+
+                if !collected.contains(&entity)
+                    && sim::touches(ball, position, power_up, power_up_position)
+                {
+                    let _ = entities.delete(entity);
+                    collected.push(entity);
+                    split_balls.extend(sim::collect_power_up(
+                        &rules,
+                        power_up.kind,
+                        side,
+                        ball,
+                        position,
+                        &mut effects,
+                    ));
+                }
+            }
+        }
+
+        for split in split_balls {
+            let mut transform = Transform::default();
+            transform.set_translation_xyz(split.position[0], split.position[1], 0.0);
+
+            let sprite = SpriteRender {
+                sprite_sheet: sheet.0.clone(),
+                sprite_number: BALL_SPRITE,
+            };
+
+            entities
+                .build_entity()
+                .with(sprite, &mut sprites)
+                .with(split.ball, &mut balls)
+                .with(split.active, &mut actives)
+                .with(transform, &mut transforms)
+                .with(Interpolated::at(split.position), &mut interpolated)
+                .build();
+        }
+    }
+}
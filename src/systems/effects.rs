@@ -0,0 +1,27 @@
+use crate::core::{Effects, FixedStep, Paddle, PADDLE_HEIGHT};
+use crate::sim;
+use amethyst::core::Transform;
+use amethyst::ecs::{Join, ReadExpect, System, Write, WriteStorage};
+
+/// Wears power-up effects off over step, and keeps the paddles in line with
+/// those still in force.
+pub struct EffectSystem;
+
+impl<'s> System<'s> for EffectSystem {
+    type SystemData = (
+        Write<'s, Effects>,
+        WriteStorage<'s, Paddle>,
+        WriteStorage<'s, Transform>,
+        ReadExpect<'s, FixedStep>,
+    );
+
+    fn run(&mut self, (mut effects, mut paddles, mut transforms, step): Self::SystemData) {
+        sim::tick_effects(&mut effects, step.dt);
+
+        for (paddle, transform) in (&mut paddles, &mut transforms).join() {
+            sim::apply_effects(&effects, paddle);
+            // The paddle sprite is drawn `PADDLE_HEIGHT` tall.
+            transform.scale_mut().y = paddle.height / PADDLE_HEIGHT;
+        }
+    }
+}
@@ -1,43 +1,56 @@
-use crate::core::{Active, Ball, ServeText};
-use amethyst::core::timing::Time;
+use crate::core::{Active, Ball, Effects, FixedStep, ServeText};
+use crate::rules::Rules;
+use crate::sim;
 use amethyst::core::{Hidden, Transform};
 use amethyst::derive::SystemDesc;
-use amethyst::ecs::{Join, Read, ReadExpect, ReadStorage, System, SystemData, WriteStorage};
+use amethyst::ecs::{Join, Read, ReadExpect, System, SystemData, WriteStorage};
 
 #[derive(SystemDesc)]
 pub struct MoveBallSystem;
 
 impl<'s> System<'s> for MoveBallSystem {
     type SystemData = (
-        ReadStorage<'s, Ball>,
+        WriteStorage<'s, Ball>,
         WriteStorage<'s, Transform>,
-        Read<'s, Time>,
+        ReadExpect<'s, FixedStep>,
         WriteStorage<'s, Active>,
         ReadExpect<'s, ServeText>,
         WriteStorage<'s, Hidden>,
+        Read<'s, Effects>,
+        ReadExpect<'s, Rules>,
     );
 
     fn run(
         &mut self,
-        (balls, mut locals, time, mut actives, serve_text, mut hiddens): Self::SystemData,
+        (
+            mut balls,
+            mut locals,
+            step,
+            mut actives,
+            serve_text,
+            mut hiddens,
+            effects,
+            rules,
+        ): Self::SystemData,
     ) {
-        // Time since the last frame.
-        let delta = time.delta_seconds();
+        // The length of a step, as the balls feel it.
+        let delta = step.dt * sim::ball_time_scale(&effects);
 
-        for (ball, local, active) in (&balls, &mut locals, &mut actives).join() {
-            match active.countdown {
-                None => {
-                    local.prepend_translation_x(ball.velocity[0] * delta);
-                    local.prepend_translation_y(ball.velocity[1] * delta);
-                }
-                Some(timer) if timer - delta < 0.0 => {
-                    active.countdown.take();
-                    let _ = hiddens.insert(serve_text.0, Hidden);
-                }
-                Some(timer) => {
-                    active.countdown.replace(timer - delta);
-                }
-            }
+        for (ball, local, active) in (&mut balls, &mut locals, &mut actives).join() {
+            let mut position = [local.translation().x, local.translation().y];
+            sim::advance_ball(&rules, ball, active, &mut position, delta);
+            local.set_translation_x(position[0]);
+            local.set_translation_y(position[1]);
+        }
+
+        // "Ready?" stays up while any ball is waiting to be served.
+        if (&balls, &actives)
+            .join()
+            .any(|(_, a)| a.countdown.is_some())
+        {
+            hiddens.remove(serve_text.0);
+        } else {
+            let _ = hiddens.insert(serve_text.0, Hidden);
         }
     }
 }
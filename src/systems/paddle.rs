@@ -1,7 +1,11 @@
-use crate::core::{Paddle, Side, ARENA_HEIGHT, PADDLE_HEIGHT};
+use crate::arena::Arena;
+use crate::core::{Ball, Controller, FixedStep, Paddle};
+use crate::replay::{Playback, Recorder};
+use crate::rules::Rules;
+use crate::sim::{self, Inputs};
 use amethyst::core::Transform;
 use amethyst::derive::SystemDesc;
-use amethyst::ecs::{Join, Read, ReadStorage, System, SystemData, WriteStorage};
+use amethyst::ecs::{Join, Read, ReadExpect, ReadStorage, System, SystemData, Write, WriteStorage};
 use amethyst::input::{InputHandler, StringBindings};
 
 #[derive(SystemDesc)]
@@ -10,26 +14,86 @@ pub struct PaddleSystem;
 impl<'s> System<'s> for PaddleSystem {
     type SystemData = (
         WriteStorage<'s, Transform>,
-        ReadStorage<'s, Paddle>,
+        WriteStorage<'s, Paddle>,
+        ReadStorage<'s, Ball>,
         Read<'s, InputHandler<StringBindings>>,
+        ReadExpect<'s, Rules>,
+        ReadExpect<'s, Arena>,
+        ReadExpect<'s, FixedStep>,
+        Option<Write<'s, Playback>>,
+        Option<Write<'s, Recorder>>,
+        Write<'s, Inputs>,
     );
 
-    fn run(&mut self, (mut transforms, paddles, input): Self::SystemData) {
-        for (paddle, transform) in (&paddles, &mut transforms).join() {
-            let movement = match paddle.side {
-                Side::Left => input.axis_value("left_paddle"),
-                Side::Right => input.axis_value("right_paddle"),
+    fn run(
+        &mut self,
+        (
+            mut transforms,
+            mut paddles,
+            balls,
+            input,
+            rules,
+            arena,
+            step,
+            playback,
+            recorder,
+            mut current,
+        ): Self::SystemData,
+    ) {
+        // A replay stands in for the players, if there is one.
+        let inputs = match playback {
+            Some(mut playback) => playback.advance(),
+            None => Inputs {
+                left: input.axis_value("left_paddle"),
+                right: input.axis_value("right_paddle"),
+                top: input.axis_value("top_paddle"),
+                bottom: input.axis_value("bottom_paddle"),
+                serve: [
+                    input.action_is_down("left_serve").unwrap_or(false),
+                    input.action_is_down("right_serve").unwrap_or(false),
+                    input.action_is_down("top_serve").unwrap_or(false),
+                    input.action_is_down("bottom_serve").unwrap_or(false),
+                ],
+            },
+        };
+        *current = inputs;
+
+        if let Some(mut recorder) = recorder {
+            recorder.0.record(inputs);
+        }
+
+        // Where every ball is, for the benefit of computer-controlled paddles.
+        let ball_positions: Vec<(&Ball, [f32; 2])> = (&balls, &transforms)
+            .join()
+            .map(|(ball, t)| (ball, [t.translation().x, t.translation().y]))
+            .collect();
+
+        let sides = sim::sides_of(paddles.join());
+
+        for (paddle, transform) in (&mut paddles, &mut transforms).join() {
+            let paddle_position = [transform.translation().x, transform.translation().y];
+
+            let movement = match paddle.controller {
+                Controller::Computer => Some(sim::computer_input(
+                    &rules,
+                    &arena,
+                    paddle,
+                    paddle_position,
+                    ball_positions.iter().copied(),
+                )),
+                Controller::Human => inputs.for_side(paddle.side),
             };
 
-            if let Some(mv_amount) = movement {
-                let scaled_amount = 1.2 * mv_amount as f32;
-                let paddle_y = transform.translation().y;
-                transform.set_translation_y(
-                    (paddle_y + scaled_amount)
-                        .min(ARENA_HEIGHT - PADDLE_HEIGHT * 0.5)
-                        .max(PADDLE_HEIGHT * 0.5),
-                );
-            }
+            let [x, y] = sim::move_paddle(
+                &arena,
+                paddle,
+                paddle_position,
+                movement.unwrap_or(0.0),
+                step.dt,
+                &sides,
+            );
+            transform.set_translation_x(x);
+            transform.set_translation_y(y);
         }
     }
 }
@@ -0,0 +1,27 @@
+use crate::core::{Rally, RallyText, ScoreBoard};
+use amethyst::ecs::{Read, System, WriteStorage};
+use amethyst::ui::UiText;
+
+/// Keeps the rally counters up to date during practice.
+pub struct RallySystem;
+
+impl<'s> System<'s> for RallySystem {
+    type SystemData = (
+        WriteStorage<'s, UiText>,
+        Option<Read<'s, RallyText>>,
+        Read<'s, Rally>,
+        Read<'s, ScoreBoard>,
+    );
+
+    fn run(&mut self, (mut ui_text, rally_text, rally, score_board): Self::SystemData) {
+        if let Some(rally_text) = rally_text {
+            if let Some(text) = ui_text.get_mut(rally_text.current) {
+                text.text = format!("Rally {}", rally.hits);
+            }
+
+            if let Some(text) = ui_text.get_mut(rally_text.best) {
+                text.text = format!("Best {}", score_board.longest_rally);
+            }
+        }
+    }
+}
@@ -1,19 +1,26 @@
+use crate::arena::Arena;
 use crate::audio;
-use crate::core::{Active, Ball, ScoreBoard, ScoreText, ServeText, ARENA_WIDTH, BALL_VELOCITY_X};
+use crate::core::{
+    Active, Ball, FixedStep, Lives, LivesText, Paddle, Rally, Random, ScoreBoard, ScoreText,
+};
+use crate::rules::Rules;
+use crate::sim;
 use amethyst::assets::AssetStorage;
 use amethyst::audio::output::Output;
 use amethyst::audio::Source;
-use amethyst::core::{Hidden, Transform};
-use amethyst::ecs::{Join, Read, ReadExpect, System, Write, WriteStorage};
+use amethyst::core::Transform;
+use amethyst::ecs::{
+    Entities, Join, Read, ReadExpect, ReadStorage, System, Write, WriteExpect, WriteStorage,
+};
 use amethyst::ui::UiText;
-use std::ops::Deref;
 
 pub struct ScoreSystem;
 
 impl<'s> System<'s> for ScoreSystem {
     type SystemData = (
+        Entities<'s>,
         WriteStorage<'s, Ball>,
-        WriteStorage<'s, Transform>,
+        ReadStorage<'s, Transform>,
         WriteStorage<'s, UiText>,
         Write<'s, ScoreBoard>,
         ReadExpect<'s, ScoreText>,
@@ -21,15 +28,22 @@ impl<'s> System<'s> for ScoreSystem {
         ReadExpect<'s, audio::Sounds>,
         Option<Read<'s, Output>>,
         WriteStorage<'s, Active>,
-        ReadExpect<'s, ServeText>,
-        WriteStorage<'s, Hidden>,
+        Write<'s, Rally>,
+        ReadExpect<'s, Rules>,
+        ReadExpect<'s, Arena>,
+        WriteExpect<'s, Random>,
+        ReadStorage<'s, Paddle>,
+        Option<Write<'s, Lives>>,
+        Option<Read<'s, LivesText>>,
+        ReadExpect<'s, FixedStep>,
     );
 
     fn run(
         &mut self,
         (
+            entities,
             mut balls,
-            mut locals,
+            locals,
             mut ui_text,
             mut scores,
             score_text,
@@ -37,47 +51,58 @@ impl<'s> System<'s> for ScoreSystem {
             sounds,
             audio_output,
             mut actives,
-            serve_text,
-            mut hiddens,
+            mut rally,
+            rules,
+            arena,
+            mut random,
+            paddles,
+            mut lives,
+            lives_text,
+            step,
         ): Self::SystemData,
     ) {
-        for (ball, transform, active) in (&mut balls, &mut locals, &mut actives).join() {
-            let ball_x = transform.translation().x;
+        scores.duration += step.dt;
+        let sides = sim::sides_of(paddles.join());
 
-            let did_hit = if ball_x <= ball.radius {
-                scores.score_right = (scores.score_right + 1).min(999);
+        for (entity, ball, transform, active) in
+            (&entities, &mut balls, &locals, &mut actives).join()
+        {
+            let position = [transform.translation().x, transform.translation().y];
 
-                if let Some(text) = ui_text.get_mut(score_text.p2_score) {
-                    text.text = scores.score_right.to_string();
+            if sim::score(
+                &rules,
+                &arena,
+                ball,
+                active,
+                position,
+                &mut scores,
+                &sides,
+                &mut random,
+            )
+            .is_some()
+            {
+                // Winning a game resets every side's points, so all are redrawn.
+                for (side, entity) in score_text.0.iter() {
+                    if let Some(text) = ui_text.get_mut(*entity) {
+                        text.text = scores.display(*side, &rules);
+                    }
                 }
 
-                true
-            } else if ball_x >= ARENA_WIDTH - ball.radius {
-                scores.score_left = (scores.score_left + 1).min(999);
+                // In breakout, the ball getting past costs a life instead.
+                if let Some(lives) = lives.as_mut() {
+                    sim::lose_life(lives, ball);
 
-                if let Some(text) = ui_text.get_mut(score_text.p1_score) {
-                    text.text = scores.score_left.to_string();
+                    if let Some(text) = lives_text.as_ref().and_then(|t| ui_text.get_mut(t.0)) {
+                        text.text = format!("Lives {}", lives.0);
+                    }
                 }
 
-                true
-            } else {
-                false
-            };
-
-            if did_hit {
-                let output = audio_output.as_ref().map(|o| o.deref());
-
-                if ball.velocity[0] < 0.0 {
-                    ball.velocity[0] = BALL_VELOCITY_X;
-                } else {
-                    ball.velocity[0] = -BALL_VELOCITY_X;
+                if ball.extra {
+                    let _ = entities.delete(entity);
                 }
 
-                ball.velocity[0] *= -1.0;
-                transform.set_translation_x(ARENA_WIDTH / 2.0);
-                audio::play_score_sound(&sounds, &storage, output);
-                active.countdown.replace(1.0);
-                hiddens.remove(serve_text.0);
+                *rally = Rally::default();
+                audio::play_score_sound(&sounds, &storage, audio_output.as_deref());
             }
         }
     }
@@ -0,0 +1,53 @@
+use crate::core::{Active, Ball, FixedStep, Paddle};
+use crate::rules::Rules;
+use crate::sim::{self, Inputs};
+use amethyst::core::Transform;
+use amethyst::ecs::{Join, Read, ReadExpect, ReadStorage, System, WriteStorage};
+
+/// Keeps balls waiting to be served on their server's paddle, and launches
+/// them when the serve comes.
+pub struct ServeSystem;
+
+impl<'s> System<'s> for ServeSystem {
+    type SystemData = (
+        ReadStorage<'s, Ball>,
+        WriteStorage<'s, Active>,
+        ReadStorage<'s, Paddle>,
+        WriteStorage<'s, Transform>,
+        Read<'s, Inputs>,
+        ReadExpect<'s, Rules>,
+        ReadExpect<'s, FixedStep>,
+    );
+
+    fn run(
+        &mut self,
+        (balls, mut actives, paddles, mut transforms, inputs, rules, step): Self::SystemData,
+    ) {
+        let paddles: Vec<(&Paddle, [f32; 2])> = (&paddles, &transforms)
+            .join()
+            .map(|(paddle, t)| (paddle, [t.translation().x, t.translation().y]))
+            .collect();
+
+        for (ball, active, transform) in (&balls, &mut actives, &mut transforms).join() {
+            let server = paddles
+                .iter()
+                .find(|(paddle, _)| Some(paddle.side) == active.held_by);
+
+            if let Some((paddle, paddle_position)) = server {
+                let mut position = [transform.translation().x, transform.translation().y];
+                sim::hold_serve(
+                    &rules,
+                    ball,
+                    active,
+                    &mut position,
+                    paddle,
+                    *paddle_position,
+                    &inputs,
+                    step.dt,
+                );
+                transform.set_translation_x(position[0]);
+                transform.set_translation_y(position[1]);
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::arena::Arena;
+use crate::core::{
+    Active, Ball, FixedStep, Interpolated, Paddle, Rally, Random, Sprites, BALL_SPRITE,
+};
+use crate::rules::Rules;
+use crate::sim;
+use amethyst::core::Transform;
+use amethyst::ecs::{
+    Entities, Join, ReadExpect, ReadStorage, System, Write, WriteExpect, WriteStorage,
+};
+use amethyst::renderer::SpriteRender;
+
+/// Brings extra balls into long rallies.
+pub struct SpawnBallSystem;
+
+impl<'s> System<'s> for SpawnBallSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, Ball>,
+        WriteStorage<'s, Active>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, SpriteRender>,
+        WriteStorage<'s, Interpolated>,
+        Write<'s, Rally>,
+        ReadExpect<'s, FixedStep>,
+        ReadExpect<'s, Rules>,
+        ReadExpect<'s, Arena>,
+        ReadExpect<'s, Sprites>,
+        WriteExpect<'s, Random>,
+        ReadStorage<'s, Paddle>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut balls,
+            mut actives,
+            mut transforms,
+            mut sprites,
+            mut interpolated,
+            mut rally,
+            step,
+            rules,
+            arena,
+            sheet,
+            mut random,
+            paddles,
+        ): Self::SystemData,
+    ) {
+        let in_play = balls.join().count();
+
+        if sim::extend_rally(&rules, &mut rally, step.dt, in_play) {
+            let sides = sim::sides_of(paddles.join());
+            let extra = sim::extra_ball(&rules, &arena, &sides, &mut random);
+            let mut transform = Transform::default();
+            transform.set_translation_xyz(extra.position[0], extra.position[1], 0.0);
+
+            let sprite = SpriteRender {
+                sprite_sheet: sheet.0.clone(),
+                sprite_number: BALL_SPRITE,
+            };
+
+            entities
+                .build_entity()
+                .with(sprite, &mut sprites)
+                .with(extra.ball, &mut balls)
+                .with(extra.active, &mut actives)
+                .with(transform, &mut transforms)
+                .with(Interpolated::at(extra.position), &mut interpolated)
+                .build();
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use crate::arena::Arena;
+use crate::core::{FixedStep, PowerUp, PowerUpTimer, Sprites};
+use crate::rules::Rules;
+use crate::sim;
+use amethyst::core::Transform;
+use amethyst::ecs::{Entities, Join, ReadExpect, System, Write, WriteStorage};
+use amethyst::renderer::SpriteRender;
+
+/// Places power-ups in the arena, when the rules call for them.
+pub struct SpawnPowerUpSystem;
+
+impl<'s> System<'s> for SpawnPowerUpSystem {
+    type SystemData = (
+        Entities<'s>,
+        WriteStorage<'s, PowerUp>,
+        WriteStorage<'s, Transform>,
+        WriteStorage<'s, SpriteRender>,
+        Write<'s, PowerUpTimer>,
+        ReadExpect<'s, FixedStep>,
+        ReadExpect<'s, Rules>,
+        ReadExpect<'s, Arena>,
+        ReadExpect<'s, Sprites>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            mut power_ups,
+            mut transforms,
+            mut sprites,
+            mut timer,
+            step,
+            rules,
+            arena,
+            sheet,
+        ): Self::SystemData,
+    ) {
+        let waiting = power_ups.join().count();
+
+        if let Some(new) = sim::power_up_due(&rules, &arena, &mut timer, step.dt, waiting) {
+            let mut transform = Transform::default();
+            transform.set_translation_xyz(new.position[0], new.position[1], 0.0);
+
+            let sprite = SpriteRender {
+                sprite_sheet: sheet.0.clone(),
+                sprite_number: new.power_up.kind.sprite_number(),
+            };
+
+            entities
+                .build_entity()
+                .with(sprite, &mut sprites)
+                .with(new.power_up, &mut power_ups)
+                .with(transform, &mut transforms)
+                .build();
+        }
+    }
+}